@@ -4,6 +4,7 @@ import (
 	cdk "github.com/aws/aws-cdk-go/awscdk/v2"
 	apigateway "github.com/aws/aws-cdk-go/awscdk/v2/awsapigateway"
 	dynamo "github.com/aws/aws-cdk-go/awscdk/v2/awsdynamodb"
+	awslambda "github.com/aws/aws-cdk-go/awscdk/v2/awslambda"
 	lambda "github.com/aws/aws-cdk-go/awscdklambdagoalpha/v2"
 	"github.com/aws/constructs-go/constructs/v10"
 	"github.com/aws/jsii-runtime-go"
@@ -20,12 +21,24 @@ func NewAwsStack(scope constructs.Construct, id string, props *ClockeryStackProp
 	}
 	stack := cdk.NewStack(scope, &id, &sprops)
 
+	// Replays submitted runs, built from `src/bin/leaderboard_check.rs` as described in README.md.
+	checkLambda := awslambda.NewFunction(stack, jsii.String("ClockeryLeaderboardCheckFunction"), &awslambda.FunctionProps{
+		Runtime: awslambda.Runtime_PROVIDED_AL2023(),
+		Handler: jsii.String("bootstrap"),
+		Code: awslambda.Code_FromAsset(jsii.String("../target/lambda/leaderboard_check"), nil),
+		MemorySize: jsii.Number(512),
+		Timeout: cdk.Duration_Seconds(jsii.Number(10)),
+	})
+
 	postLambda := lambda.NewGoFunction(stack, jsii.String("ClockeryLeaderboardPostFunction"), &lambda.GoFunctionProps{
 		Entry: jsii.String("lambda/leaderboard/post/post.go"),
 		Environment: &map[string]*string{
 			"TABLE_NAME": jsii.String("ClockeryLeaderboard"),
+			"CHECK_FUNCTION_NAME": checkLambda.FunctionName(),
 		},
+		Timeout: cdk.Duration_Seconds(jsii.Number(15)),
 	})
+	checkLambda.GrantInvoke(postLambda)
 
 	getLambda := lambda.NewGoFunction(stack, jsii.String("ClockeryLeaderboardGetFunction"), &lambda.GoFunctionProps{
 		Entry: jsii.String("lambda/leaderboard/get-all/get.go"),
@@ -6,6 +6,7 @@ import (
 	"os"
 	"sort"
 	"strconv"
+	"time"
 
 	"github.com/aws/aws-lambda-go/events"
 	"github.com/aws/aws-lambda-go/lambda"
@@ -19,9 +20,44 @@ type LeaderboardRecord struct {
 	Id string `json:"id"`
 	Name string `json:"name"`
 	Score float64 `json:"score"`
+	Mode string `json:"mode"`
+	Difficulty string `json:"difficulty"`
+	Version string `json:"version"`
+	DurationSecs float64 `json:"duration_secs"`
+	Date int64 `json:"date"`
 }
 
-func HandleRequest(ctx context.Context) (*events.APIGatewayProxyResponse, error) {
+// Scores stored before categories existed are solo, normal and undated.
+func stringOr(item map[string]types.AttributeValue, key string, fallback string) string {
+	if value, ok := item[key].(*types.AttributeValueMemberS); ok {
+		return value.Value
+	}
+	return fallback
+}
+
+func numberOr(item map[string]types.AttributeValue, key string) float64 {
+	if value, ok := item[key].(*types.AttributeValueMemberN); ok {
+		if number, err := strconv.ParseFloat(value.Value, 64); err == nil {
+			return number
+		}
+	}
+	return 0
+}
+
+// The oldest date a score can have to be in the `window` query parameter.
+func since(window string) int64 {
+	now := time.Now().Unix()
+	switch window {
+	case "daily":
+		return now - 24*60*60
+	case "weekly":
+		return now - 7*24*60*60
+	default:
+		return 0
+	}
+}
+
+func HandleRequest(ctx context.Context, req events.APIGatewayProxyRequest) (*events.APIGatewayProxyResponse, error) {
 	cfg, err := config.LoadDefaultConfig(ctx)
 	if err != nil {
 		return nil, err
@@ -36,7 +72,17 @@ func HandleRequest(ctx context.Context) (*events.APIGatewayProxyResponse, error)
 		return nil, err
 	}
 
-	var entries []LeaderboardRecord
+	mode := req.QueryStringParameters["mode"]
+	if mode == "" {
+		mode = "solo"
+	}
+	difficulty := req.QueryStringParameters["difficulty"]
+	if difficulty == "" {
+		difficulty = "normal"
+	}
+	oldest := since(req.QueryStringParameters["window"])
+
+	entries := []LeaderboardRecord{}
 	for _, item := range result.Items {
 		score, err := strconv.ParseFloat(item["score"].(*types.AttributeValueMemberN).Value, 32)
 		if err != nil {
@@ -44,11 +90,20 @@ func HandleRequest(ctx context.Context) (*events.APIGatewayProxyResponse, error)
 		}
 		s := score
 
-		entries = append(entries, LeaderboardRecord{
+		record := LeaderboardRecord{
 			Id: item["id"].(*types.AttributeValueMemberS).Value,
 			Name: item["name"].(*types.AttributeValueMemberS).Value,
 			Score: s,
-		})
+			Mode: stringOr(item, "mode", "solo"),
+			Difficulty: stringOr(item, "difficulty", "normal"),
+			Version: stringOr(item, "version", ""),
+			DurationSecs: numberOr(item, "duration_secs"),
+			Date: int64(numberOr(item, "date")),
+		}
+		if record.Mode != mode || record.Difficulty != difficulty || record.Date < oldest {
+			continue
+		}
+		entries = append(entries, record)
 	}
 	sort.Slice(entries, func(i, j int) bool {
 		return entries[i].Score > entries[j].Score
@@ -5,6 +5,7 @@ import (
 	"encoding/json"
 	"fmt"
 	"os"
+	"time"
 
 	"github.com/aws/aws-lambda-go/events"
 	"github.com/aws/aws-lambda-go/lambda"
@@ -12,18 +13,41 @@ import (
 	"github.com/aws/aws-sdk-go-v2/config"
 	"github.com/aws/aws-sdk-go-v2/service/dynamodb"
 	"github.com/aws/aws-sdk-go-v2/service/dynamodb/types"
+	lambdaservice "github.com/aws/aws-sdk-go-v2/service/lambda"
 	"github.com/google/uuid"
 )
 
 type LeaderboardEvent struct {
 	Name string `json:"name"`
 	Score float64 `json:"score"`
+	Mode string `json:"mode"`
+	Difficulty string `json:"difficulty"`
+	Version string `json:"version"`
+	DurationSecs float64 `json:"duration_secs"`
+}
+
+// The categories with a leaderboard, the keys of the game's
+// GameMode::RANKED and Difficulty::RANKED.
+var rankedModes = map[string]bool{"solo": true, "coop": true}
+var rankedDifficulties = map[string]bool{"easy": true, "normal": true, "hard": true}
+
+// What the score check function, `src/bin/leaderboard_check.rs`, made of a submission.
+type CheckResult struct {
+	Result string `json:"result"`
+	// The name to store the score under, checked with the game's name rules.
+	Name string `json:"name"`
+	Error string `json:"error"`
 }
 
 type LeaderboardResponse struct {
 	Id string `json:"id"`
 	Name string `json:"name"`
 	Score float64 `json:"score"`
+	Mode string `json:"mode"`
+	Difficulty string `json:"difficulty"`
+	Version string `json:"version"`
+	DurationSecs float64 `json:"duration_secs"`
+	Date int64 `json:"date"`
 }
 
 func HandleRequest(ctx context.Context, req events.APIGatewayProxyRequest) (*events.APIGatewayProxyResponse, error) {
@@ -40,8 +64,9 @@ func HandleRequest(ctx context.Context, req events.APIGatewayProxyRequest) (*eve
 	}
 
 	event := LeaderboardEvent{}
-	json.Unmarshal([]byte(req.Body), &event)
-	
+	if err := json.Unmarshal([]byte(req.Body), &event); err != nil {
+		return badRequest("Bad Request"), nil
+	}
 
 	if event.Name == "" {
 		return nil, fmt.Errorf("name is required")
@@ -51,6 +76,36 @@ func HandleRequest(ctx context.Context, req events.APIGatewayProxyRequest) (*eve
 		return nil, fmt.Errorf("score is required")
 	}
 
+	if event.Mode == "" {
+		event.Mode = "solo"
+	}
+	if event.Difficulty == "" {
+		event.Difficulty = "normal"
+	}
+	// Unranked runs, like versus or custom difficulty ones, have no category to go in.
+	if !rankedModes[event.Mode] || !rankedDifficulties[event.Difficulty] {
+		return badRequest(fmt.Sprintf("%s %s runs aren't ranked", event.Mode, event.Difficulty)), nil
+	}
+	// Checks the name with the game's rules, and replays the run from its summary
+	// so scores aren't taken on trust.
+	check, err := checkSubmission(ctx, cfg, req.Body)
+	if err != nil {
+		return nil, err
+	}
+	if check.Result != "accepted" {
+		return &events.APIGatewayProxyResponse{
+			Headers: map[string]string{
+				"Access-Control-Allow-Origin": "*",
+				},
+			StatusCode: 422,
+			Body: check.Error,
+		}, nil
+	}
+	event.Name = check.Name
+
+	// Dated here rather than by the game, so scores can't be dated in the future.
+	date := time.Now().Unix()
+
 	dynamo := dynamodb.NewFromConfig(cfg)
 
 	id := uuid.New()
@@ -61,6 +116,11 @@ func HandleRequest(ctx context.Context, req events.APIGatewayProxyRequest) (*eve
 			"id": &types.AttributeValueMemberS{Value: id.String()},
 			"name": &types.AttributeValueMemberS{Value: event.Name},
 			"score": &types.AttributeValueMemberN{Value: fmt.Sprintf("%f", event.Score)},
+			"mode": &types.AttributeValueMemberS{Value: event.Mode},
+			"difficulty": &types.AttributeValueMemberS{Value: event.Difficulty},
+			"version": &types.AttributeValueMemberS{Value: event.Version},
+			"duration_secs": &types.AttributeValueMemberN{Value: fmt.Sprintf("%f", event.DurationSecs)},
+			"date": &types.AttributeValueMemberN{Value: fmt.Sprintf("%d", date)},
 		},
 	})
 
@@ -68,6 +128,11 @@ func HandleRequest(ctx context.Context, req events.APIGatewayProxyRequest) (*eve
 		Id: id.String(),
 		Name: event.Name,
 		Score: event.Score,
+		Mode: event.Mode,
+		Difficulty: event.Difficulty,
+		Version: event.Version,
+		DurationSecs: event.DurationSecs,
+		Date: date,
 	})
 	if err != nil {
 		return nil, err
@@ -82,6 +147,35 @@ func HandleRequest(ctx context.Context, req events.APIGatewayProxyRequest) (*eve
 	}, nil
 }
 
+// Run the submission past the score check function named by CHECK_FUNCTION_NAME.
+func checkSubmission(ctx context.Context, cfg aws.Config, body string) (*CheckResult, error) {
+	out, err := lambdaservice.NewFromConfig(cfg).Invoke(ctx, &lambdaservice.InvokeInput{
+		FunctionName: aws.String(os.Getenv("CHECK_FUNCTION_NAME")),
+		Payload: []byte(body),
+	})
+	if err != nil {
+		return nil, err
+	}
+	if out.FunctionError != nil {
+		return nil, fmt.Errorf("score check failed: %s", *out.FunctionError)
+	}
+	check := CheckResult{}
+	if err := json.Unmarshal(out.Payload, &check); err != nil {
+		return nil, err
+	}
+	return &check, nil
+}
+
+func badRequest(message string) *events.APIGatewayProxyResponse {
+	return &events.APIGatewayProxyResponse{
+		Headers: map[string]string{
+			"Access-Control-Allow-Origin": "*",
+			},
+		StatusCode: 400,
+		Body: message,
+	}
+}
+
 func main() {
 	lambda.Start(HandleRequest)
 }
\ No newline at end of file
@@ -0,0 +1,65 @@
+//! Exports the run history kept on this device, for balancing.
+//! Writes every run as JSON, or one CSV row per run, or with `--format csv --timeline`
+//! one CSV row per second of every run.
+//!
+//! ```sh
+//! cargo run --bin export_history -- [--format json|csv] [--timeline] [--out <file>]
+//! ```
+
+use std::{env, fs, process::ExitCode};
+
+use clockery::history::RunHistory;
+
+fn main() -> ExitCode {
+    let mut csv = false;
+    let mut timeline = false;
+    let mut out = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => match args.next().as_deref() {
+                Some("json") => csv = false,
+                Some("csv") => csv = true,
+                _ => return usage(),
+            },
+            "--timeline" => timeline = true,
+            "--out" => match args.next() {
+                Some(path) => out = Some(path),
+                None => return usage(),
+            },
+            _ => return usage(),
+        }
+    }
+    let history = RunHistory::load();
+    if history.0.is_empty() {
+        eprintln!("No runs in {}", RunHistory::location());
+    }
+    // Runs exported as JSON already carry their timeline.
+    let export = match (csv, timeline) {
+        (true, true) => history.timeline_csv(),
+        (true, false) => history.to_csv(),
+        (false, _) => history.to_json(),
+    };
+
+    match out {
+        Some(path) => match fs::write(&path, export) {
+            Ok(()) => {
+                eprintln!("Exported {} runs to {path}", history.0.len());
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("Couldn't write {path}: {e}");
+                ExitCode::FAILURE
+            }
+        },
+        None => {
+            print!("{export}");
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+fn usage() -> ExitCode {
+    eprintln!("Usage: export_history [--format json|csv] [--timeline] [--out <file>]");
+    ExitCode::from(2)
+}
@@ -0,0 +1,122 @@
+//! The leaderboard's score check, run as an AWS Lambda function on an OS-only runtime.
+//! `aws/lambda/leaderboard/post` invokes it with the body of every submission
+//! and only stores the score if it's accepted. Submissions are accepted if the name
+//! passes the game's [`NameRules`] and their run summary is signed and replays to
+//! the submitted score, so those without one are rejected.
+//!
+//! Talks to the Lambda runtime API at `AWS_LAMBDA_RUNTIME_API`, see `aws/README.md`
+//! for how to build and deploy it.
+
+use std::{
+    env,
+    io::{self, Read, Write},
+    net::TcpStream,
+    process::ExitCode,
+};
+
+use serde::Serialize;
+
+use clockery::leaderboard::{run_key, LeaderboardBody, NameRules};
+
+const RUNTIME_API: &str = "/2018-06-01/runtime/invocation";
+
+/// What the check made of a submission, as returned to the post function.
+#[derive(Serialize, Debug)]
+#[serde(tag = "result", rename_all = "lowercase")]
+enum Check {
+    /// With the name to store the score under.
+    Accepted {
+        name: String,
+    },
+    Rejected {
+        error: String,
+    },
+}
+
+fn main() -> ExitCode {
+    let Ok(api) = env::var("AWS_LAMBDA_RUNTIME_API") else {
+        eprintln!("AWS_LAMBDA_RUNTIME_API isn't set, this only runs as a Lambda function");
+        return ExitCode::from(2);
+    };
+    loop {
+        // Lambda stops the function when something goes wrong talking to it.
+        let result = next_invocation(&api).and_then(|(id, payload)| {
+            let check = check(&payload);
+            let json = serde_json::to_string(&check).map_err(io::Error::other)?;
+            request(&api, "POST", &format!("{RUNTIME_API}/{id}/response"), &json).map(|_| ())
+        });
+        if let Err(e) = result {
+            eprintln!("Lambda runtime API failed: {e}");
+            return ExitCode::FAILURE;
+        }
+    }
+}
+
+fn check(payload: &[u8]) -> Check {
+    let body = match serde_json::from_slice::<LeaderboardBody>(payload) {
+        Ok(body) => body,
+        Err(e) => {
+            return Check::Rejected {
+                error: format!("unreadable submission: {e}"),
+            }
+        }
+    };
+    let name = match NameRules::default().validate(&body.name) {
+        Ok(name) => name,
+        Err(e) => {
+            return Check::Rejected {
+                error: e.to_string(),
+            }
+        }
+    };
+    match body.verify(run_key()) {
+        Ok(()) => Check::Accepted { name },
+        Err(e) => Check::Rejected {
+            error: e.to_string(),
+        },
+    }
+}
+
+/// Wait for the next submission to check, returning its request id and payload.
+fn next_invocation(api: &str) -> io::Result<(String, Vec<u8>)> {
+    let (headers, payload) = request(api, "GET", &format!("{RUNTIME_API}/next"), "")?;
+    let id = headers
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.eq_ignore_ascii_case("lambda-runtime-aws-request-id"))
+        .map(|(_, value)| value.trim().to_string())
+        .ok_or_else(|| io::Error::other("invocation without a request id"))?;
+    Ok((id, payload))
+}
+
+/// Send a request to the runtime API, returning the response headers and body.
+/// HTTP/1.0, so the body is never chunked and ends when the connection closes.
+fn request(api: &str, method: &str, path: &str, body: &str) -> io::Result<(String, Vec<u8>)> {
+    let mut stream = TcpStream::connect(api)?;
+    write!(
+        stream,
+        "{method} {path} HTTP/1.0\r\n\
+         Host: {api}\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: {}\r\n\
+         \r\n\
+         {body}",
+        body.len(),
+    )?;
+    stream.flush()?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)?;
+    let split = response
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .ok_or_else(|| io::Error::other("malformed response"))?;
+    let headers = String::from_utf8_lossy(&response[..split]).into_owned();
+    let status = headers.split_whitespace().nth(1).unwrap_or_default();
+    if !status.starts_with('2') {
+        return Err(io::Error::other(format!(
+            "{method} {path} returned {status}"
+        )));
+    }
+    Ok((headers, response[split + 4..].to_vec()))
+}
@@ -0,0 +1,260 @@
+//! A local stand-in for the leaderboard web API, for development and CI.
+//!
+//! Serves `GET /leaderboard` and `POST /leaderboard` like `aws/lambda/leaderboard`,
+//! storing the scores in a JSON file. `GET` takes the `mode`, `difficulty` and `window`
+//! query parameters the game sends to select a category. Names are checked with the same rules as in the game,
+//! optionally with another blocklist. Submissions are replayed from their run summary and rejected
+//! if the score doesn't hold up. Those without one are rejected too, unless `--allow-unsigned` is given.
+//! Point the game at it with
+//! `--leaderboard http://localhost:3000/leaderboard` on native or
+//! `?leaderboard=http://localhost:3000/leaderboard` on web.
+//!
+//! ```sh
+//! cargo run --bin leaderboard_server -- --port 3000 --file leaderboard.json [--blocklist names.txt] [--key <run key>] [--allow-unsigned]
+//! ```
+
+use std::{
+    env, fs,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    path::PathBuf,
+    time::Duration,
+};
+
+use clockery::leaderboard::{
+    run_key, unix_now, LeaderboardBody, NameRules, RunInfo, ScoreFile, ScoreFilter, VerifyError,
+};
+
+const DEFAULT_PORT: u16 = 3000;
+const DEFAULT_FILE: &str = "leaderboard.json";
+/// Refuse request bodies larger than this. Run summaries hold every input of the run.
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+/// Give up on a connection that stalls for this long, so it can't hold up the others.
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+fn main() -> io::Result<()> {
+    let mut port = DEFAULT_PORT;
+    let mut file = PathBuf::from(DEFAULT_FILE);
+    let mut rules = NameRules::default();
+    let mut key = run_key().to_vec();
+    let mut allow_unsigned = false;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--allow-unsigned" {
+            allow_unsigned = true;
+            continue;
+        }
+        match (arg.as_str(), args.next()) {
+            ("--port", Some(value)) => {
+                port = value.parse().map_err(io::Error::other)?;
+            }
+            ("--file", Some(value)) => file = PathBuf::from(value),
+            ("--blocklist", Some(value)) => {
+                rules = NameRules::with_blocklist(&fs::read_to_string(value)?);
+            }
+            ("--key", Some(value)) => key = value.into_bytes(),
+            _ => {
+                eprintln!(
+                    "Usage: leaderboard_server [--port <port>] [--file <path>] [--blocklist <path>] \
+                     [--key <run key>] [--allow-unsigned]"
+                );
+                std::process::exit(2);
+            }
+        }
+    }
+
+    let server = Server {
+        scores: ScoreFile::new(file),
+        rules,
+        key,
+        allow_unsigned,
+    };
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    println!(
+        "Serving http://localhost:{port}/leaderboard from {}",
+        server.scores.path().display()
+    );
+
+    // One request at a time keeps the file consistent without any locking.
+    for stream in listener.incoming() {
+        let result = stream.and_then(|stream| handle_connection(stream, &server));
+        if let Err(e) = result {
+            eprintln!("Connection failed: {e}");
+        }
+    }
+    Ok(())
+}
+
+struct Server {
+    scores: ScoreFile,
+    rules: NameRules,
+    /// The key run summaries are signed with.
+    key: Vec<u8>,
+    /// Store submissions without a run summary, unchecked.
+    allow_unsigned: bool,
+}
+
+struct Request {
+    method: String,
+    path: String,
+    body: Vec<u8>,
+}
+
+struct Response {
+    status: &'static str,
+    body: String,
+}
+
+impl Response {
+    fn json(body: String) -> Self {
+        Self {
+            status: "200 OK",
+            body,
+        }
+    }
+
+    fn error(status: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: message.into(),
+        }
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, server: &Server) -> io::Result<()> {
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+    let response = match read_request(&mut stream)? {
+        Some(request) => {
+            let response = route(&request, server);
+            println!("{} {} -> {}", request.method, request.path, response.status);
+            response
+        }
+        None => Response::error("400 Bad Request", "Bad Request"),
+    };
+    write_response(&mut stream, &response)
+}
+
+fn route(request: &Request, server: &Server) -> Response {
+    let (path, query) = request
+        .path
+        .split_once('?')
+        .unwrap_or((request.path.as_str(), ""));
+    if path.trim_end_matches('/') != "/leaderboard" {
+        return Response::error("404 Not Found", "Not Found");
+    }
+
+    match request.method.as_str() {
+        // CORS preflight, the headers are on every response.
+        "OPTIONS" => Response::json(String::new()),
+        "GET" => match server.scores.scores() {
+            Ok(records) => to_json(&ScoreFilter::from_query(query).apply(records)),
+            Err(e) => Response::error("500 Internal Server Error", e.to_string()),
+        },
+        "POST" => {
+            let Ok(body) = serde_json::from_slice::<LeaderboardBody>(&request.body) else {
+                return Response::error("400 Bad Request", "Bad Request");
+            };
+            let name = match server.rules.validate(&body.name) {
+                Ok(name) => name,
+                Err(e) => return Response::error("400 Bad Request", e.to_string()),
+            };
+            if body.score == 0.0 {
+                return Response::error("400 Bad Request", "score is required");
+            }
+            if !body.run.ranked() {
+                return Response::error("400 Bad Request", VerifyError::Unranked.to_string());
+            }
+            match body.verify(&server.key) {
+                Ok(()) => {}
+                Err(VerifyError::Unsigned) if server.allow_unsigned => {}
+                Err(e) => return Response::error("422 Unprocessable Entity", e.to_string()),
+            }
+            let body = LeaderboardBody {
+                name,
+                // Dated here, so a score can't be kept in the daily and weekly
+                // lists by dating it in the future.
+                run: RunInfo {
+                    date: unix_now(),
+                    ..body.run
+                },
+                // Only needed to check the score, don't store it.
+                summary: None,
+                ..body
+            };
+            match server.scores.submit(body) {
+                Ok(record) => to_json(&record),
+                Err(e) => Response::error("500 Internal Server Error", e.to_string()),
+            }
+        }
+        _ => Response::error("405 Method Not Allowed", "Method Not Allowed"),
+    }
+}
+
+fn to_json(value: &impl serde::Serialize) -> Response {
+    match serde_json::to_string(value) {
+        Ok(json) => Response::json(json),
+        Err(e) => Response::error("500 Internal Server Error", e.to_string()),
+    }
+}
+
+/// Read a single HTTP/1.1 request. Returns `None` if it's malformed.
+fn read_request(stream: &mut TcpStream) -> io::Result<Option<Request>> {
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+        return Ok(None);
+    };
+    let (method, path) = (method.to_string(), path.to_string());
+
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                match value.trim().parse() {
+                    Ok(length) if length <= MAX_BODY_BYTES => content_length = length,
+                    _ => return Ok(None),
+                }
+            }
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    Ok(Some(Request { method, path, body }))
+}
+
+fn write_response(stream: &mut TcpStream, response: &Response) -> io::Result<()> {
+    let content_type = if response.status.starts_with("200") {
+        "application/json"
+    } else {
+        "text/plain"
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\n\
+         Content-Type: {content_type}\r\n\
+         Content-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\n\
+         Access-Control-Allow-Methods: GET, POST, OPTIONS\r\n\
+         Access-Control-Allow-Headers: Content-Type\r\n\
+         Connection: close\r\n\
+         \r\n\
+         {}",
+        response.status,
+        response.body.len(),
+        response.body,
+    )?;
+    stream.flush()
+}
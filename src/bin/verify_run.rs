@@ -0,0 +1,65 @@
+//! Checks a score submission by playing its run back, for backends that can't link the game.
+//! Reads the JSON body sent to `POST /leaderboard` from a file or stdin, and exits
+//! with an error if the run summary is missing or the score doesn't hold up.
+//!
+//! ```sh
+//! cargo run --bin verify_run -- [--key <run key>] [submission.json]
+//! ```
+
+use std::{
+    env, fs,
+    io::{self, Read},
+    process::ExitCode,
+};
+
+use clockery::leaderboard::{run_key, LeaderboardBody};
+
+fn main() -> ExitCode {
+    let mut key = run_key().to_vec();
+    let mut path = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), &path) {
+            ("--key", _) => match args.next() {
+                Some(value) => key = value.into_bytes(),
+                None => return usage(),
+            },
+            (_, None) if !arg.starts_with("--") => path = Some(arg),
+            _ => return usage(),
+        }
+    }
+
+    let json = match path {
+        Some(path) => fs::read_to_string(path),
+        None => {
+            let mut json = String::new();
+            io::stdin().read_to_string(&mut json).map(|_| json)
+        }
+    };
+    let body = match json
+        .map_err(|e| e.to_string())
+        .and_then(|json| serde_json::from_str::<LeaderboardBody>(&json).map_err(|e| e.to_string()))
+    {
+        Ok(body) => body,
+        Err(e) => {
+            eprintln!("Couldn't read submission: {e}");
+            return ExitCode::from(2);
+        }
+    };
+
+    match body.verify(&key) {
+        Ok(()) => {
+            println!("OK: {:.2} for {}", body.score, body.name);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            println!("Rejected: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn usage() -> ExitCode {
+    eprintln!("Usage: verify_run [--key <run key>] [submission.json]");
+    ExitCode::from(2)
+}
@@ -0,0 +1,175 @@
+//! Options for players who find the clocks hard to read, the motion too much or can't hear:
+//! high-contrast faces and hands, thicker hands, colourblind-safe sync colours,
+//! reduced motion, captions and bigger UI text.
+//! Picked on the title screen and kept on this device.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::spawn::clock::ClockHandType;
+use crate::{leaderboard::storage, ui::prelude::*};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<AccessibilityOption>();
+    let settings = AccessibilitySettings::load();
+    app.insert_resource(TextScale(settings.text_scale));
+    app.insert_resource(settings);
+    app.add_systems(Update, (style_clock_hands, apply_text_scale));
+}
+
+const ACCESSIBILITY_KEY: &str = "accessibility";
+
+/// The text sizes to pick from, in order.
+pub const TEXT_SCALES: [f32; 3] = [1.0, 1.25, 1.5];
+/// How much wider the hands are with [`AccessibilityOption::ThickHands`].
+const THICK_HAND_WIDTH: f32 = 1.8;
+const HIGH_CONTRAST_HAND: Color = Color::BLACK;
+/// How far high contrast pulls a tinted face back towards white.
+const HIGH_CONTRAST_FACE_MIX: f32 = 0.75;
+
+/// The options that can be turned on or off.
+#[derive(Serialize, Deserialize, Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccessibilityOption {
+    HighContrast,
+    ThickHands,
+    Colourblind,
+    ReducedMotion,
+    Captions,
+}
+
+impl AccessibilityOption {
+    pub const ALL: [Self; 5] = [
+        Self::HighContrast,
+        Self::ThickHands,
+        Self::Colourblind,
+        Self::ReducedMotion,
+        Self::Captions,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::HighContrast => "Contrast",
+            Self::ThickHands => "Thick hands",
+            Self::Colourblind => "Colourblind",
+            Self::ReducedMotion => "Less motion",
+            Self::Captions => "Captions",
+        }
+    }
+}
+
+#[derive(Resource, Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct AccessibilitySettings {
+    /// Black clock hands on near-white faces.
+    pub high_contrast: bool,
+    pub thick_hands: bool,
+    /// Blue and orange instead of green and red for sync.
+    pub colourblind: bool,
+    /// No spinning gear on the title screen, and no screen shake.
+    pub reduced_motion: bool,
+    /// Show what the important sounds are saying, see [`Caption`](super::captions::Caption).
+    pub captions: bool,
+    /// One of [`TEXT_SCALES`].
+    pub text_scale: f32,
+}
+
+impl Default for AccessibilitySettings {
+    fn default() -> Self {
+        Self {
+            high_contrast: false,
+            thick_hands: false,
+            colourblind: false,
+            reduced_motion: false,
+            captions: false,
+            text_scale: TEXT_SCALES[0],
+        }
+    }
+}
+
+impl AccessibilitySettings {
+    fn load() -> Self {
+        storage::load(ACCESSIBILITY_KEY).unwrap_or_default()
+    }
+
+    pub fn enabled(&self, option: AccessibilityOption) -> bool {
+        match option {
+            AccessibilityOption::HighContrast => self.high_contrast,
+            AccessibilityOption::ThickHands => self.thick_hands,
+            AccessibilityOption::Colourblind => self.colourblind,
+            AccessibilityOption::ReducedMotion => self.reduced_motion,
+            AccessibilityOption::Captions => self.captions,
+        }
+    }
+
+    pub fn toggle(&mut self, option: AccessibilityOption) {
+        let enabled = match option {
+            AccessibilityOption::HighContrast => &mut self.high_contrast,
+            AccessibilityOption::ThickHands => &mut self.thick_hands,
+            AccessibilityOption::Colourblind => &mut self.colourblind,
+            AccessibilityOption::ReducedMotion => &mut self.reduced_motion,
+            AccessibilityOption::Captions => &mut self.captions,
+        };
+        *enabled = !*enabled;
+        storage::save(ACCESSIBILITY_KEY, self);
+    }
+
+    /// Go to the next of the [`TEXT_SCALES`], back to the first after the last.
+    pub fn next_text_scale(&mut self) {
+        let current = TEXT_SCALES
+            .iter()
+            .position(|&scale| scale == self.text_scale)
+            .unwrap_or(0);
+        self.text_scale = TEXT_SCALES[(current + 1) % TEXT_SCALES.len()];
+        storage::save(ACCESSIBILITY_KEY, self);
+    }
+
+    /// The colour of a running clock's hands.
+    pub fn hand_color(&self) -> Color {
+        if self.high_contrast {
+            HIGH_CONTRAST_HAND
+        } else {
+            Color::WHITE
+        }
+    }
+
+    /// The colour of a clock's face, given the tint it would have otherwise.
+    /// High contrast keeps stopped and drifting faces light, so the black hands still read.
+    pub fn face_color(&self, tint: Color) -> Color {
+        if self.high_contrast {
+            tint.mix(&Color::WHITE, HIGH_CONTRAST_FACE_MIX)
+        } else {
+            tint
+        }
+    }
+
+    pub fn text_scale_label(&self) -> String {
+        format!("Text {:.0}%", self.text_scale * 100.0)
+    }
+}
+
+/// Restyle every hand when the settings change, and new hands as they're spawned.
+fn style_clock_hands(
+    settings: Res<AccessibilitySettings>,
+    mut hand_query: Query<(Ref<ClockHandType>, &mut Sprite, &mut Transform)>,
+) {
+    let color = settings.hand_color();
+    let width = if settings.thick_hands {
+        THICK_HAND_WIDTH
+    } else {
+        1.0
+    };
+    for (hand, mut sprite, mut transform) in &mut hand_query {
+        if !settings.is_changed() && !hand.is_added() {
+            continue;
+        }
+        sprite.color = color;
+        // Scaled along the hand's own x, so it stays thick as it turns.
+        transform.scale.x = width;
+    }
+}
+
+fn apply_text_scale(settings: Res<AccessibilitySettings>, mut scale: ResMut<TextScale>) {
+    if settings.is_changed() && scale.0 != settings.text_scale {
+        scale.0 = settings.text_scale;
+    }
+}
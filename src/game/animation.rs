@@ -3,13 +3,24 @@
 //! - [Sprite flipping](https://github.com/bevyengine/bevy/blob/latest/examples/2d/sprite_flipping.rs)
 //! - [Sprite animation](https://github.com/bevyengine/bevy/blob/latest/examples/2d/sprite_animation.rs)
 //! - [Timers](https://github.com/bevyengine/bevy/blob/latest/examples/time/timers.rs)
+//!
+//! Tockery is a single drawing until there's art for frames, so each state
+//! is animated by an [`AnimationClip`]'s [`Pose`], which moves the whole sprite.
 
-use std::time::Duration;
+use std::{f32::consts::TAU, time::Duration};
 
 use bevy::prelude::*;
 
-use super::{audio::sfx::PlaySfx, movement::MovementController};
-use crate::AppSet;
+use super::{
+    audio::{bank::SfxCue, sfx::PlaySfx},
+    movement::MovementController,
+    run::GameInput,
+    spawn::{clock::ClockController, player::Player},
+};
+use crate::{
+    leaderboard::replay::{TickInput, OIL_STATION},
+    AppSet,
+};
 
 pub(super) fn plugin(app: &mut App) {
     // Animate and play sound effects based on controls.
@@ -19,8 +30,8 @@ pub(super) fn plugin(app: &mut App) {
         (
             update_animation_timer.in_set(AppSet::TickTimers),
             (
-                update_animation_movement,
-                update_animation_atlas,
+                update_animation_state,
+                update_animation_pose,
                 trigger_step_sfx,
             )
                 .chain()
@@ -29,20 +40,155 @@ pub(super) fn plugin(app: &mut App) {
     );
 }
 
-/// Update the sprite direction and animation state (idling/walking).
-fn update_animation_movement(
-    mut player_query: Query<(&MovementController, &mut Sprite, &mut PlayerAnimation)>,
+/// How the whole sprite leans and stretches on top of the drawing.
+/// Angles are in radians, leaning towards where the player faces,
+/// and stretches are fractions of the sprite's height.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Pose {
+    pub lean: f32,
+    pub sway: f32,
+    pub stretch: f32,
+    pub bob: f32,
+    /// How many times a second the sway and bob go back and forth.
+    pub hz: f32,
+}
+
+impl Pose {
+    fn transform(&self, secs: f32, facing: f32) -> (Quat, Vec3) {
+        let wave = (secs * self.hz * TAU).sin();
+        let angle = -facing * (self.lean + self.sway * wave);
+        let stretch = self.stretch + self.bob * wave;
+        let scale = Vec3::new(1.0 - stretch / 2.0, 1.0 + stretch, 1.0);
+        (Quat::from_rotation_z(angle), scale)
+    }
+}
+
+/// How the player looks in one [`PlayerAnimationState`].
+#[derive(Debug, Clone, Copy)]
+pub struct AnimationClip {
+    pub state: PlayerAnimationState,
+    /// How often a foot lands, if the player is walking.
+    pub step_interval: Option<Duration>,
+    pub pose: Pose,
+}
+
+/// The animation for `state` in `clips`, or the first one if there's none for it.
+fn find_clip(clips: &[AnimationClip], state: PlayerAnimationState) -> &AnimationClip {
+    clips
+        .iter()
+        .find(|clip| clip.state == state)
+        .unwrap_or(&clips[0])
+}
+
+/// Tockery's animations, one for each state.
+pub const TOCKERY_CLIPS: &[AnimationClip] = &[
+    AnimationClip {
+        state: PlayerAnimationState::Idling,
+        step_interval: None,
+        // Breathing.
+        pose: Pose {
+            lean: 0.0,
+            sway: 0.0,
+            stretch: 0.0,
+            bob: 0.015,
+            hz: 0.4,
+        },
+    },
+    AnimationClip {
+        state: PlayerAnimationState::Walking,
+        step_interval: Some(Duration::from_millis(150)),
+        pose: Pose {
+            lean: 0.06,
+            sway: 0.04,
+            stretch: 0.0,
+            bob: 0.025,
+            hz: 6.5,
+        },
+    },
+    AnimationClip {
+        state: PlayerAnimationState::Carrying,
+        step_interval: None,
+        // Leaning back under the weight.
+        pose: Pose {
+            lean: -0.04,
+            sway: 0.0,
+            stretch: -0.03,
+            bob: 0.01,
+            hz: 0.8,
+        },
+    },
+    AnimationClip {
+        state: PlayerAnimationState::Winding,
+        step_interval: None,
+        pose: Pose {
+            lean: 0.02,
+            sway: 0.05,
+            stretch: -0.02,
+            bob: 0.01,
+            hz: 4.0,
+        },
+    },
+    AnimationClip {
+        state: PlayerAnimationState::Setting,
+        step_interval: None,
+        // Leaning in to see the hands.
+        pose: Pose {
+            lean: 0.05,
+            sway: 0.015,
+            stretch: 0.0,
+            bob: 0.0,
+            hz: 2.0,
+        },
+    },
+    AnimationClip {
+        state: PlayerAnimationState::Drinking,
+        step_interval: None,
+        // Tipping back for the last drop.
+        pose: Pose {
+            lean: -0.12,
+            sway: 0.0,
+            stretch: 0.02,
+            bob: 0.02,
+            hz: 3.0,
+        },
+    },
+];
+
+/// Update the sprite direction and animation state from the player's movement
+/// and what they're doing with their hands.
+fn update_animation_state(
+    input: Res<GameInput>,
+    mut player_query: Query<(
+        &Player,
+        &MovementController,
+        &ClockController,
+        &mut Sprite,
+        &mut PlayerAnimation,
+    )>,
 ) {
-    for (controller, mut sprite, mut animation) in &mut player_query {
-        let dx = controller.0.x;
+    for (player, movement, controller, mut sprite, mut animation) in &mut player_query {
+        let dx = movement.0.x;
         if dx != 0.0 {
             sprite.flip_x = dx < 0.0;
         }
 
-        let animation_state = if controller.0 == Vec2::ZERO {
+        let drinking = controller.held_clock.is_none()
+            && controller.index == OIL_STATION
+            && input
+                .player(player.0)
+                .held(TickInput::ACTION | TickInput::WIND | TickInput::SET);
+        let animation_state = if movement.0 != Vec2::ZERO {
+            PlayerAnimationState::Walking
+        } else if drinking {
+            PlayerAnimationState::Drinking
+        } else if controller.held_clock.is_none() {
             PlayerAnimationState::Idling
+        } else if controller.winding {
+            PlayerAnimationState::Winding
+        } else if controller.setting {
+            PlayerAnimationState::Setting
         } else {
-            PlayerAnimationState::Walking
+            PlayerAnimationState::Carrying
         };
         animation.update_state(animation_state);
     }
@@ -55,107 +201,86 @@ fn update_animation_timer(time: Res<Time>, mut query: Query<&mut PlayerAnimation
     }
 }
 
-/// Update the texture atlas to reflect changes in the animation.
-fn update_animation_atlas(mut query: Query<(&PlayerAnimation, &mut TextureAtlas)>) {
-    for (animation, mut atlas) in &mut query {
-        if animation.changed() {
-            atlas.index = animation.get_atlas_index();
-        }
+/// Lean and stretch the sprite for the current state.
+fn update_animation_pose(mut query: Query<(&PlayerAnimation, &Sprite, &mut Transform)>) {
+    for (animation, sprite, mut transform) in &mut query {
+        let facing = if sprite.flip_x { -1.0 } else { 1.0 };
+        let (rotation, scale) = animation.clip().pose.transform(animation.secs, facing);
+        transform.rotation = rotation;
+        transform.scale = scale;
     }
 }
 
 /// If the player is moving, play a step sound effect synchronized with the animation.
 fn trigger_step_sfx(mut commands: Commands, mut step_query: Query<&PlayerAnimation>) {
     for animation in &mut step_query {
-        if animation.state == PlayerAnimationState::Walking
-            && animation.changed()
-            && (animation.frame == 2 || animation.frame == 5)
-        {
-            commands.trigger(PlaySfx::RandomStep);
+        if animation.stepped() {
+            commands.trigger(PlaySfx::Cue(SfxCue::Step));
         }
     }
 }
 
 /// Component that tracks player's animation state.
-/// It is tightly bound to the texture atlas we use.
+/// Looks are taken from its [`AnimationClip`]s.
 #[derive(Component, Reflect)]
 #[reflect(Component)]
 pub struct PlayerAnimation {
     timer: Timer,
-    frame: usize,
+    /// Seconds spent in this state, for the pose.
+    secs: f32,
     state: PlayerAnimationState,
+    #[reflect(ignore)]
+    clips: &'static [AnimationClip],
 }
 
-#[derive(Reflect, PartialEq)]
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PlayerAnimationState {
     Idling,
     Walking,
+    Carrying,
+    Winding,
+    Setting,
+    Drinking,
 }
 
 impl PlayerAnimation {
-    /// The number of idle frames.
-    const IDLE_FRAMES: usize = 2;
-    /// The duration of each idle frame.
-    const IDLE_INTERVAL: Duration = Duration::from_millis(500);
-
-    fn idling() -> Self {
-        Self {
-            timer: Timer::new(Self::IDLE_INTERVAL, TimerMode::Repeating),
-            frame: 0,
-            state: PlayerAnimationState::Idling,
-        }
+    pub fn new(clips: &'static [AnimationClip]) -> Self {
+        Self::playing(clips, PlayerAnimationState::Idling)
     }
 
-    /// The number of walking frames.
-    const WALKING_FRAMES: usize = 6;
-    /// The duration of each walking frame.
-    const WALKING_INTERVAL: Duration = Duration::from_millis(50);
-
-    fn walking() -> Self {
+    fn playing(clips: &'static [AnimationClip], state: PlayerAnimationState) -> Self {
         Self {
-            timer: Timer::new(Self::WALKING_INTERVAL, TimerMode::Repeating),
-            frame: 0,
-            state: PlayerAnimationState::Walking,
+            timer: Timer::new(
+                find_clip(clips, state)
+                    .step_interval
+                    .unwrap_or(Duration::MAX),
+                TimerMode::Repeating,
+            ),
+            secs: 0.0,
+            state,
+            clips,
         }
     }
 
-    pub fn new() -> Self {
-        Self::idling()
+    fn clip(&self) -> &AnimationClip {
+        find_clip(self.clips, self.state)
     }
 
     /// Update animation timers.
     pub fn update_timer(&mut self, delta: Duration) {
+        self.secs += delta.as_secs_f32();
         self.timer.tick(delta);
-        if !self.timer.finished() {
-            return;
-        }
-        self.frame = (self.frame + 1)
-            % match self.state {
-                PlayerAnimationState::Idling => Self::IDLE_FRAMES,
-                PlayerAnimationState::Walking => Self::WALKING_FRAMES,
-            };
     }
 
     /// Update animation state if it changes.
     pub fn update_state(&mut self, state: PlayerAnimationState) {
         if self.state != state {
-            match state {
-                PlayerAnimationState::Idling => *self = Self::idling(),
-                PlayerAnimationState::Walking => *self = Self::walking(),
-            }
+            *self = Self::playing(self.clips, state);
         }
     }
 
-    /// Whether animation changed this tick.
-    pub fn changed(&self) -> bool {
+    /// Whether a foot landed this tick.
+    pub fn stepped(&self) -> bool {
         self.timer.finished()
     }
-
-    /// Return sprite index in the atlas.
-    pub fn get_atlas_index(&self) -> usize {
-        match self.state {
-            PlayerAnimationState::Idling => self.frame,
-            PlayerAnimationState::Walking => 6 + self.frame,
-        }
-    }
 }
@@ -368,12 +368,6 @@ pub enum SfxKey {
     OilDrink,
     MenuTick,
     MenuTock,
-    Ticking1,
-    Ticking2,
-    Ticking3,
-    Ticking4,
-    Ticking5,
-    Ticking6,
     Setting1,
     Setting2,
     Setting3,
@@ -388,6 +382,7 @@ pub enum SfxKey {
     ClockSpawn2,
     ClockSpawn3,
     ClockSpawn4,
+    ClockWarning,
 }
 
 impl AssetKey for SfxKey {
@@ -419,30 +414,6 @@ impl FromWorld for HandleMap<SfxKey> {
                 SfxKey::MenuTock,
                 asset_server.load("audio/sfx/menu-tock.wav"),
             ),
-            (
-                SfxKey::Ticking1,
-                asset_server.load("audio/sfx/ticking-1.wav"),
-            ),
-            (
-                SfxKey::Ticking2,
-                asset_server.load("audio/sfx/ticking-2.wav"),
-            ),
-            (
-                SfxKey::Ticking3,
-                asset_server.load("audio/sfx/ticking-3.wav"),
-            ),
-            (
-                SfxKey::Ticking4,
-                asset_server.load("audio/sfx/ticking-4.wav"),
-            ),
-            (
-                SfxKey::Ticking5,
-                asset_server.load("audio/sfx/ticking-5.wav"),
-            ),
-            (
-                SfxKey::Ticking6,
-                asset_server.load("audio/sfx/ticking-6.wav"),
-            ),
             (SfxKey::Setting1, asset_server.load("audio/sfx/set-1.wav")),
             (SfxKey::Setting2, asset_server.load("audio/sfx/set-2.wav")),
             (SfxKey::Setting3, asset_server.load("audio/sfx/set-3.wav")),
@@ -481,6 +452,10 @@ impl FromWorld for HandleMap<SfxKey> {
                 SfxKey::ClockSpawn4,
                 asset_server.load("audio/sfx/new-clock-4.wav"),
             ),
+            (
+                SfxKey::ClockWarning,
+                asset_server.load("audio/sfx/clock-warning.wav"),
+            ),
         ]
         .into()
     }
@@ -0,0 +1,179 @@
+//! Sound bank definitions for one-shot sound effects.
+//! Gameplay code asks for an [`SfxCue`], and the bank decides which
+//! [`SfxKey`] variant actually plays and how.
+
+use bevy::{prelude::*, utils::HashMap};
+use rand::{seq::SliceRandom, Rng};
+
+use crate::game::assets::SfxKey;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<SfxCue>();
+    app.init_resource::<SoundBank>();
+    app.init_resource::<SfxCueCooldowns>();
+}
+
+/// A named sound effect that can have several variants.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Reflect)]
+pub enum SfxCue {
+    ButtonHover,
+    ButtonPress,
+    MenuClick,
+    Step,
+    ClockDown,
+    ClockSpawn,
+    /// A clock is about to run down.
+    ClockWarning,
+    /// A clock has run down.
+    ClockStopped,
+}
+
+/// How a single [`SfxCue`] is played.
+#[derive(Clone, Copy)]
+pub struct SoundCue {
+    /// The sound effects to pick from, with their relative weights.
+    pub variants: &'static [(SfxKey, f32)],
+    /// Range to pick the playback speed from. This shifts the pitch.
+    pub pitch: (f32, f32),
+    /// Range to pick the volume from.
+    pub volume: (f32, f32),
+    /// Minimum time in seconds between two plays of this cue.
+    pub cooldown_secs: f32,
+    /// How many instances of this cue may play at the same time.
+    pub max_voices: usize,
+}
+
+impl SoundCue {
+    /// A cue with no variants, played as recorded.
+    pub const DEFAULT: Self = Self {
+        variants: &[],
+        pitch: (1.0, 1.0),
+        volume: (1.0, 1.0),
+        cooldown_secs: 0.0,
+        max_voices: 4,
+    };
+
+    /// Pick a variant by weight, or `None` if the cue has no variants.
+    pub fn choose_variant(&self, rng: &mut impl Rng) -> Option<SfxKey> {
+        self.variants
+            .choose_weighted(rng, |(_, weight)| *weight)
+            .ok()
+            .map(|(key, _)| *key)
+    }
+
+    pub fn choose_pitch(&self, rng: &mut impl Rng) -> f32 {
+        rng.gen_range(self.pitch.0..=self.pitch.1)
+    }
+
+    pub fn choose_volume(&self, rng: &mut impl Rng) -> f32 {
+        rng.gen_range(self.volume.0..=self.volume.1)
+    }
+}
+
+/// How every cue is played. Tune the sound effects here.
+const SOUND_CUES: &[(SfxCue, SoundCue)] = &[
+    (
+        SfxCue::ButtonHover,
+        SoundCue {
+            variants: &[(SfxKey::ButtonHover, 1.0)],
+            cooldown_secs: 0.05,
+            max_voices: 1,
+            ..SoundCue::DEFAULT
+        },
+    ),
+    (
+        SfxCue::ButtonPress,
+        SoundCue {
+            variants: &[(SfxKey::ButtonPress, 1.0)],
+            max_voices: 1,
+            ..SoundCue::DEFAULT
+        },
+    ),
+    (
+        SfxCue::MenuClick,
+        SoundCue {
+            variants: &[(SfxKey::MenuTick, 1.0), (SfxKey::MenuTock, 1.0)],
+            pitch: (0.95, 1.05),
+            cooldown_secs: 0.06,
+            max_voices: 2,
+            ..SoundCue::DEFAULT
+        },
+    ),
+    (
+        SfxCue::Step,
+        SoundCue {
+            variants: &[
+                (SfxKey::Step1, 1.0),
+                (SfxKey::Step2, 1.0),
+                (SfxKey::Step3, 1.0),
+                (SfxKey::Step4, 1.0),
+            ],
+            pitch: (0.9, 1.1),
+            volume: (0.8, 1.0),
+            max_voices: 2,
+            ..SoundCue::DEFAULT
+        },
+    ),
+    (
+        SfxCue::ClockDown,
+        SoundCue {
+            variants: &[
+                (SfxKey::ClockDown1, 1.0),
+                (SfxKey::ClockDown2, 1.0),
+                (SfxKey::ClockDown3, 1.0),
+                (SfxKey::ClockDown4, 1.0),
+            ],
+            pitch: (0.95, 1.05),
+            cooldown_secs: 0.1,
+            max_voices: 2,
+            ..SoundCue::DEFAULT
+        },
+    ),
+    (
+        SfxCue::ClockSpawn,
+        SoundCue {
+            variants: &[
+                (SfxKey::ClockSpawn1, 1.0),
+                (SfxKey::ClockSpawn2, 1.0),
+                (SfxKey::ClockSpawn3, 1.0),
+                (SfxKey::ClockSpawn4, 1.0),
+            ],
+            max_voices: 1,
+            ..SoundCue::DEFAULT
+        },
+    ),
+    (
+        SfxCue::ClockWarning,
+        SoundCue {
+            variants: &[(SfxKey::ClockWarning, 1.0)],
+            volume: (0.6, 0.6),
+            cooldown_secs: 0.2,
+            max_voices: 2,
+            ..SoundCue::DEFAULT
+        },
+    ),
+    (
+        SfxCue::ClockStopped,
+        SoundCue {
+            variants: &[(SfxKey::ClockWarning, 1.0)],
+            pitch: (0.5, 0.5),
+            volume: (0.8, 0.8),
+            max_voices: 1,
+            ..SoundCue::DEFAULT
+        },
+    ),
+];
+
+/// The cues from [`SOUND_CUES`], by name.
+#[derive(Resource, Deref, DerefMut)]
+pub struct SoundBank(HashMap<SfxCue, SoundCue>);
+
+impl Default for SoundBank {
+    fn default() -> Self {
+        Self(SOUND_CUES.iter().copied().collect())
+    }
+}
+
+/// When each cue was last played, in real seconds since startup.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub(super) struct SfxCueCooldowns(HashMap<SfxCue, f32>);
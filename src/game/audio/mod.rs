@@ -1,8 +1,15 @@
+pub mod bank;
 pub mod sfx;
 pub mod soundtrack;
+pub mod ticking;
 
 use bevy::prelude::*;
 
 pub fn plugin(app: &mut App) {
-    app.add_plugins((sfx::plugin, soundtrack::plugin));
+    app.add_plugins((
+        bank::plugin,
+        sfx::plugin,
+        soundtrack::plugin,
+        ticking::plugin,
+    ));
 }
@@ -2,8 +2,8 @@ use bevy::{
     audio::{PlaybackMode, Volume},
     prelude::*,
 };
-use rand::seq::SliceRandom;
 
+use super::bank::{SfxCue, SfxCueCooldowns, SoundBank};
 use crate::{
     game::assets::{HandleMap, SfxKey},
     screen::Screen,
@@ -35,16 +35,6 @@ fn play_looping_sfx(
         PlayLoopingSfx::Key(key) => *key,
     };
 
-    let is_tick = matches!(
-        sfx_key,
-        SfxKey::Ticking1
-            | SfxKey::Ticking2
-            | SfxKey::Ticking3
-            | SfxKey::Ticking4
-            | SfxKey::Ticking5
-            | SfxKey::Ticking6
-    );
-
     let handle = sfx_handles[&sfx_key].clone_weak();
     let state = sfx_playing
         .states
@@ -52,15 +42,17 @@ fn play_looping_sfx(
         .find(|(key, _)| *key == sfx_key);
 
     let mut play = false;
-    if state.is_none() {
-        sfx_playing.states.push((sfx_key, true));
-        play = true;
-    } else {
-        let (_, playing) = state.unwrap();
-        if !*playing {
-            *playing = true;
+    match state {
+        None => {
+            sfx_playing.states.push((sfx_key, true));
             play = true;
         }
+        Some((_, playing)) => {
+            if !*playing {
+                *playing = true;
+                play = true;
+            }
+        }
     }
 
     if play {
@@ -69,11 +61,7 @@ fn play_looping_sfx(
                 source: handle,
                 settings: PlaybackSettings {
                     mode: PlaybackMode::Loop,
-                    volume: if is_tick {
-                        Volume::new(2.0)
-                    } else {
-                        Volume::new(1.0)
-                    },
+                    volume: Volume::new(1.0),
                     ..default()
                 },
             },
@@ -140,28 +128,60 @@ fn stop_all_looping_sfx(
 fn play_sfx(
     trigger: Trigger<PlaySfx>,
     mut commands: Commands,
+    // Real time, so cooldowns hold through hit-stop and whichever schedule triggered the cue.
+    time: Res<Time<Real>>,
     sfx_handles: Res<HandleMap<SfxKey>>,
+    bank: Res<SoundBank>,
+    mut cooldowns: ResMut<SfxCueCooldowns>,
+    voices: Query<&SfxVoice>,
 ) {
-    let sfx_key = match trigger.event() {
-        PlaySfx::Key(key) => *key,
-        PlaySfx::RandomStep => random_step(),
+    let cue = match trigger.event() {
+        PlaySfx::Cue(cue) => *cue,
+    };
+    let Some(sound) = bank.get(&cue) else {
+        return;
+    };
+
+    let now = time.elapsed_seconds();
+    if let Some(last_played) = cooldowns.get(&cue) {
+        if now - last_played < sound.cooldown_secs {
+            return;
+        }
+    }
+    if voices.iter().filter(|voice| voice.0 == cue).count() >= sound.max_voices {
+        return;
+    }
+
+    let mut rng = rand::thread_rng();
+    let Some(sfx_key) = sound.choose_variant(&mut rng) else {
+        return;
     };
-    commands.spawn(AudioSourceBundle {
-        source: sfx_handles[&sfx_key].clone_weak(),
-        settings: PlaybackSettings {
-            mode: PlaybackMode::Despawn,
-            ..default()
+    cooldowns.insert(cue, now);
+
+    commands.spawn((
+        AudioSourceBundle {
+            source: sfx_handles[&sfx_key].clone_weak(),
+            settings: PlaybackSettings {
+                mode: PlaybackMode::Despawn,
+                volume: Volume::new(sound.choose_volume(&mut rng)),
+                speed: sound.choose_pitch(&mut rng),
+                ..default()
+            },
         },
-    });
+        SfxVoice(cue),
+    ));
 }
 
-/// Trigger this event to play a single sound effect.
+/// Trigger this event to play a single sound effect from the [`SoundBank`].
 #[derive(Event)]
 pub enum PlaySfx {
-    Key(SfxKey),
-    RandomStep,
+    Cue(SfxCue),
 }
 
+/// A one-shot sound effect that is still playing.
+#[derive(Component)]
+struct SfxVoice(SfxCue);
+
 #[derive(Event)]
 pub enum PlayLoopingSfx {
     Key(SfxKey),
@@ -174,10 +194,3 @@ pub enum StopLoopingSfx {
 
 #[derive(Event)]
 pub struct StopAllLoopingSfx;
-
-fn random_step() -> SfxKey {
-    [SfxKey::Step1, SfxKey::Step2, SfxKey::Step3, SfxKey::Step4]
-        .choose(&mut rand::thread_rng())
-        .copied()
-        .unwrap()
-}
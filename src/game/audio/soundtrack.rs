@@ -3,21 +3,97 @@ use bevy::{
     prelude::*,
 };
 
-use crate::game::assets::{HandleMap, SoundtrackKey};
+use crate::{
+    game::assets::{HandleMap, SoundtrackKey},
+    screen::{PlayingState, Screen},
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<IsSoundtrack>();
+    app.register_type::<SoundtrackFade>();
     app.observe(play_soundtrack);
+
+    app.insert_resource(SoundtrackSchedule {
+        crossfade_secs: 1.5,
+        tracks: vec![
+            (Screen::Splash, None, SoundtrackKey::Menu),
+            (Screen::Loading, None, SoundtrackKey::Menu),
+            (Screen::Title, None, SoundtrackKey::Menu),
+            (Screen::Leaderboard, None, SoundtrackKey::Menu),
+            (Screen::Credits, None, SoundtrackKey::Credits),
+            (
+                Screen::Playing,
+                Some(PlayingState::GameOver),
+                SoundtrackKey::Credits,
+            ),
+            (Screen::Playing, None, SoundtrackKey::Gameplay),
+        ],
+    });
+
+    app.add_systems(
+        Update,
+        (
+            apply_soundtrack_schedule
+                .run_if(state_changed::<Screen>.or_else(state_changed::<PlayingState>)),
+            fade_soundtracks,
+        ),
+    );
+}
+
+/// The volume soundtracks play at once fully faded in.
+const SOUNDTRACK_VOLUME: f32 = 0.3;
+
+/// Which soundtrack plays for which screen.
+/// Entries are matched in order; a `None` playing state matches any.
+#[derive(Resource)]
+pub struct SoundtrackSchedule {
+    pub crossfade_secs: f32,
+    pub tracks: Vec<(Screen, Option<PlayingState>, SoundtrackKey)>,
+}
+
+impl SoundtrackSchedule {
+    pub fn track_for(&self, screen: &Screen, playing: &PlayingState) -> Option<SoundtrackKey> {
+        self.tracks
+            .iter()
+            .find(|(s, p, _)| s == screen && p.as_ref().is_none_or(|p| p == playing))
+            .map(|(_, _, key)| *key)
+    }
+}
+
+fn apply_soundtrack_schedule(
+    mut commands: Commands,
+    schedule: Res<SoundtrackSchedule>,
+    screen: Res<State<Screen>>,
+    playing: Res<State<PlayingState>>,
+    soundtrack_query: Query<&IsSoundtrack, Without<SoundtrackFadeOut>>,
+) {
+    let Some(key) = schedule.track_for(screen.get(), playing.get()) else {
+        return;
+    };
+    if soundtrack_query
+        .iter()
+        .any(|soundtrack| soundtrack.0 == key)
+    {
+        return;
+    }
+    commands.trigger(PlaySoundtrack::Key(key));
 }
 
 fn play_soundtrack(
     trigger: Trigger<PlaySoundtrack>,
     mut commands: Commands,
+    schedule: Res<SoundtrackSchedule>,
     soundtrack_handles: Res<HandleMap<SoundtrackKey>>,
-    soundtrack_query: Query<Entity, With<IsSoundtrack>>,
+    soundtrack_query: Query<(Entity, Option<&SoundtrackFade>), With<IsSoundtrack>>,
 ) {
-    for entity in &soundtrack_query {
-        commands.entity(entity).despawn_recursive();
+    let duration = schedule.crossfade_secs;
+
+    for (entity, fade) in &soundtrack_query {
+        // Start fading out from wherever a fade-in got to.
+        let from = fade.map_or(SOUNDTRACK_VOLUME, SoundtrackFade::volume);
+        commands
+            .entity(entity)
+            .insert((SoundtrackFade::new(from, 0.0, duration), SoundtrackFadeOut));
     }
 
     let soundtrack_key = match trigger.event() {
@@ -28,17 +104,51 @@ fn play_soundtrack(
             source: soundtrack_handles[&soundtrack_key].clone_weak(),
             settings: PlaybackSettings {
                 mode: PlaybackMode::Loop,
-                volume: Volume::new(0.3),
+                volume: Volume::new(if duration > 0.0 {
+                    0.0
+                } else {
+                    SOUNDTRACK_VOLUME
+                }),
                 ..default()
             },
         },
-        IsSoundtrack,
+        IsSoundtrack(soundtrack_key),
+        SoundtrackFade::new(0.0, SOUNDTRACK_VOLUME, duration),
     ));
 }
 
+fn fade_soundtracks(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut fade_query: Query<(
+        Entity,
+        &mut SoundtrackFade,
+        Option<&AudioSink>,
+        Has<SoundtrackFadeOut>,
+    )>,
+) {
+    for (entity, mut fade, sink, fading_out) in &mut fade_query {
+        fade.timer.tick(time.delta());
+        if let Some(sink) = sink {
+            sink.set_volume(fade.volume());
+        }
+
+        if fade.timer.finished() {
+            if fading_out {
+                commands.entity(entity).despawn_recursive();
+            } else {
+                commands.entity(entity).remove::<SoundtrackFade>();
+            }
+        }
+    }
+}
+
 /// Trigger this event to play or disable the soundtrack.
-/// Playing a new soundtrack will overwrite the previous one.
+/// Playing a new soundtrack will crossfade out the previous one.
 /// Soundtracks will loop.
+///
+/// Screens don't need to trigger this themselves, the [`SoundtrackSchedule`]
+/// picks the soundtrack whenever the screen changes.
 #[derive(Event)]
 pub enum PlaySoundtrack {
     Key(SoundtrackKey),
@@ -47,4 +157,31 @@ pub enum PlaySoundtrack {
 /// Marker component for the soundtrack entity so we can find it later.
 #[derive(Component, Reflect)]
 #[reflect(Component)]
-struct IsSoundtrack;
+struct IsSoundtrack(SoundtrackKey);
+
+/// Marker component for a soundtrack that is being replaced.
+#[derive(Component)]
+struct SoundtrackFadeOut;
+
+/// Linear volume ramp for a soundtrack entity.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct SoundtrackFade {
+    from: f32,
+    to: f32,
+    timer: Timer,
+}
+
+impl SoundtrackFade {
+    fn new(from: f32, to: f32, duration_secs: f32) -> Self {
+        Self {
+            from,
+            to,
+            timer: Timer::from_seconds(duration_secs, TimerMode::Once),
+        }
+    }
+
+    fn volume(&self) -> f32 {
+        self.from.lerp(self.to, self.timer.fraction())
+    }
+}
@@ -0,0 +1,253 @@
+//! Procedurally synthesized clock ticking.
+//! Every clock owns a [`TickSynth`] audio source whose tick rate follows how
+//! fast the clock's minute hand is actually turning, so winding, setting and
+//! running down are all audible.
+//! Based on the [decodable example](https://github.com/bevyengine/bevy/blob/release-0.14.0/examples/audio/decodable.rs).
+
+use std::{
+    f32::consts::TAU,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use bevy::{
+    audio::{AddAudioSource, Source},
+    prelude::*,
+};
+
+use crate::{
+    game::spawn::clock::{Clock, ClockHandType, FULL_WIND_SECS},
+    screen::Screen,
+    AppSet,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_audio_source::<TickSynth>();
+    app.add_systems(
+        FixedUpdate,
+        update_tick_rates
+            .after(AppSet::FixedUpdate)
+            .after(AppSet::Update)
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+/// Minute hand speed of a clock running normally, in radians per second.
+const NORMAL_MINUTE_SPEED: f32 = 0.1047198 * 2.0;
+/// Ticks per second of a clock running normally.
+const NORMAL_TICKS_PER_SEC: f32 = 2.0;
+/// How fast a clock with almost no wind left ticks, compared to a fully wound one.
+const RUN_DOWN_TICK_SCALE: f32 = 0.5;
+/// Above this the clicks blur into a buzz, so stop speeding up.
+const MAX_TICKS_PER_SEC: f32 = 30.0;
+
+const SAMPLE_RATE: u32 = 44_100;
+
+/// The sound of a single clock's tick and tock.
+#[derive(Clone, Copy, Debug, Reflect)]
+pub struct TickTimbre {
+    /// Pitch of the ringing part of a tick, in Hz.
+    pub tick_hz: f32,
+    /// Pitch of the ringing part of a tock, in Hz.
+    pub tock_hz: f32,
+    /// How quickly a click dies out, in 1/seconds.
+    pub decay: f32,
+    /// How much of a click is noise instead of ringing, between 0 and 1.
+    pub noise: f32,
+    pub volume: f32,
+}
+
+/// Shared tick rate between the ECS and the audio thread.
+#[derive(Clone, Default)]
+pub struct TickRate(Arc<AtomicU32>);
+
+impl TickRate {
+    pub fn set(&self, ticks_per_sec: f32) {
+        self.0.store(ticks_per_sec.to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> f32 {
+        f32::from_bits(self.0.load(Ordering::Relaxed))
+    }
+}
+
+/// An endless audio source that clicks at the rate in its [`TickRate`].
+#[derive(Asset, TypePath)]
+pub struct TickSynth {
+    rate: TickRate,
+    timbre: TickTimbre,
+}
+
+impl TickSynth {
+    pub fn new(rate: TickRate, timbre: TickTimbre) -> Self {
+        Self { rate, timbre }
+    }
+}
+
+impl Decodable for TickSynth {
+    type DecoderItem = <TickDecoder as Iterator>::Item;
+    type Decoder = TickDecoder;
+
+    fn decoder(&self) -> Self::Decoder {
+        TickDecoder {
+            rate: self.rate.clone(),
+            timbre: self.timbre,
+            ticks_per_sec: 0.0,
+            until_next_tick: 0.0,
+            tock: false,
+            envelope: 0.0,
+            frequency: 0.0,
+            phase: 0.0,
+            noise_state: 0x9e37_79b9,
+            sample: 0,
+        }
+    }
+}
+
+pub struct TickDecoder {
+    rate: TickRate,
+    timbre: TickTimbre,
+    ticks_per_sec: f32,
+    /// Seconds until the next click starts.
+    until_next_tick: f32,
+    tock: bool,
+    envelope: f32,
+    frequency: f32,
+    phase: f32,
+    noise_state: u32,
+    sample: u32,
+}
+
+impl TickDecoder {
+    fn noise(&mut self) -> f32 {
+        // xorshift32, plenty for a click.
+        self.noise_state ^= self.noise_state << 13;
+        self.noise_state ^= self.noise_state >> 17;
+        self.noise_state ^= self.noise_state << 5;
+        self.noise_state as f32 / u32::MAX as f32 * 2.0 - 1.0
+    }
+}
+
+impl Iterator for TickDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let dt = 1.0 / SAMPLE_RATE as f32;
+
+        // Reading the atomic every sample is wasteful, the rate changes slowly.
+        if self.sample.is_multiple_of(64) {
+            self.ticks_per_sec = self.rate.get();
+        }
+        self.sample = self.sample.wrapping_add(1);
+
+        if self.ticks_per_sec > 0.0 {
+            self.until_next_tick -= dt;
+            if self.until_next_tick <= 0.0 {
+                self.until_next_tick += 1.0 / self.ticks_per_sec;
+                // Don't let a long silence queue up a burst of clicks.
+                self.until_next_tick = self.until_next_tick.max(0.0);
+                self.envelope = 1.0;
+                self.phase = 0.0;
+                self.frequency = if self.tock {
+                    self.timbre.tock_hz
+                } else {
+                    self.timbre.tick_hz
+                };
+                self.tock = !self.tock;
+            }
+        }
+
+        if self.envelope < 0.001 {
+            return Some(0.0);
+        }
+
+        self.phase = (self.phase + self.frequency * dt * TAU) % TAU;
+        let ring = self.phase.sin();
+        let noise = self.noise();
+        let sample = (ring * (1.0 - self.timbre.noise) + noise * self.timbre.noise)
+            * self.envelope
+            * self.timbre.volume;
+        self.envelope *= (-self.timbre.decay * dt).exp();
+
+        Some(sample)
+    }
+}
+
+impl Source for TickDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Tracks a clock's minute hand to drive its [`TickSynth`].
+#[derive(Component)]
+pub struct ClockTicker {
+    pub rate: TickRate,
+    last_minute: Option<Quat>,
+}
+
+impl ClockTicker {
+    pub fn new(rate: TickRate) -> Self {
+        Self {
+            rate,
+            last_minute: None,
+        }
+    }
+}
+
+/// Convert how far each minute hand turned this step into a tick rate,
+/// slowed down as the clock runs out of wind.
+fn update_tick_rates(
+    time: Res<Time>,
+    mut clocks: Query<(&mut ClockTicker, &Clock, &Children)>,
+    hands: Query<(&Transform, &ClockHandType)>,
+) {
+    let dt = time.delta_seconds();
+    if dt <= 0.0 {
+        return;
+    }
+
+    for (mut ticker, clock, children) in &mut clocks {
+        let Some(minute) = children.iter().find_map(|&child| match hands.get(child) {
+            Ok((transform, ClockHandType::Minute)) => Some(transform.rotation),
+            _ => None,
+        }) else {
+            continue;
+        };
+
+        let speed = ticker
+            .last_minute
+            .map_or(0.0, |last| last.angle_between(minute) / dt);
+        ticker.last_minute = Some(minute);
+
+        let wind = if clock.is_main {
+            1.0
+        } else {
+            (clock.time_left / FULL_WIND_SECS).clamp(0.0, 1.0)
+        };
+        let ticks_per_sec = (speed / NORMAL_MINUTE_SPEED
+            * NORMAL_TICKS_PER_SEC
+            * RUN_DOWN_TICK_SCALE.lerp(1.0, wind))
+        .min(MAX_TICKS_PER_SEC);
+        ticker.rate.set(if ticks_per_sec > 0.05 {
+            ticks_per_sec
+        } else {
+            0.0
+        });
+    }
+}
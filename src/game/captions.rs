@@ -0,0 +1,236 @@
+//! Captions for the sounds that tell the player something, for players who can't hear them.
+//! Gameplay code triggers a [`Caption`] next to the matching sound. With captions
+//! turned on it shows as a line at the bottom of the screen, and as a marker
+//! over the clock the sound came from.
+
+use std::mem::discriminant;
+
+use bevy::prelude::*;
+
+use super::{accessibility::AccessibilitySettings, spawn::clock::CLOCK_RADIUS};
+use crate::{screen::Screen, ui::prelude::*, AppSet};
+
+pub(super) fn plugin(app: &mut App) {
+    app.observe(show_caption);
+    app.add_systems(OnEnter(Screen::Playing), spawn_caption_panel);
+    app.add_systems(
+        Update,
+        (fade_captions, fade_markers)
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+const CAPTION_SECS: f32 = 2.5;
+/// Captions fade out over their last this many seconds.
+const FADE_SECS: f32 = 0.5;
+const MAX_CAPTIONS: usize = 3;
+const MARKER_SECS: f32 = 1.5;
+const MARKER_Y: f32 = CLOCK_RADIUS + 28.0;
+const MARKER_FONT_SIZE: f32 = 30.0;
+const MARKER_COLOR: Color = Color::srgb(1.0, 0.95, 0.6);
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Caption {
+    pub cue: CaptionCue,
+    /// The clock the sound came from, to put a marker over.
+    pub source: Option<Entity>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptionCue {
+    ClockSpawned,
+    /// A clock sent over by the other player in versus.
+    DriftingClock,
+    /// A clock's warning, with how many seconds it has left.
+    RunningDown(u32),
+    ClockStopped,
+    /// How fast the hands are being set, from 1 to 4.
+    SettingSpeed(u8),
+}
+
+impl CaptionCue {
+    fn text(self) -> String {
+        match self {
+            Self::ClockSpawned => "[a new clock rings]".to_string(),
+            Self::DriftingClock => "[a drifting clock rattles]".to_string(),
+            Self::RunningDown(secs) => format!("[a clock ticks down: {secs}]"),
+            Self::ClockStopped => "[a clock stops ticking]".to_string(),
+            Self::SettingSpeed(speed) => format!("[hands whirring, speed {speed}]"),
+        }
+    }
+
+    fn marker(self) -> String {
+        match self {
+            Self::ClockSpawned => "NEW".to_string(),
+            Self::DriftingClock => "~~".to_string(),
+            Self::RunningDown(secs) => format!("!{secs}"),
+            Self::ClockStopped => "STOP".to_string(),
+            Self::SettingSpeed(speed) => ">".repeat(speed as usize),
+        }
+    }
+
+    /// A new caption replaces the last one if it's the same kind,
+    /// so a countdown doesn't fill up the screen.
+    fn same_kind(self, other: Self) -> bool {
+        discriminant(&self) == discriminant(&other)
+    }
+}
+
+#[derive(Component)]
+struct CaptionPanel;
+
+#[derive(Component)]
+struct CaptionLine {
+    cue: CaptionCue,
+    age: f32,
+}
+
+#[derive(Component)]
+struct CaptionMarker {
+    age: f32,
+}
+
+fn spawn_caption_panel(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Captions"),
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                bottom: Val::Px(20.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(4.0),
+                ..default()
+            },
+            ..default()
+        },
+        CaptionPanel,
+        StateScoped(Screen::Playing),
+    ));
+}
+
+fn show_caption(
+    trigger: Trigger<Caption>,
+    mut commands: Commands,
+    settings: Res<AccessibilitySettings>,
+    panel_query: Query<Entity, With<CaptionPanel>>,
+    mut line_query: Query<(Entity, &mut CaptionLine, &mut Text), Without<CaptionMarker>>,
+    mut marker_query: Query<(&Parent, &mut CaptionMarker, &mut Text), Without<CaptionLine>>,
+) {
+    if !settings.captions {
+        return;
+    }
+    let Caption { cue, source } = *trigger.event();
+
+    let newest = line_query
+        .iter_mut()
+        .filter(|(_, line, _)| line.cue.same_kind(cue))
+        .min_by(|(_, a, _), (_, b, _)| a.age.total_cmp(&b.age));
+    if let Some((_, mut line, mut text)) = newest {
+        line.cue = cue;
+        line.age = 0.0;
+        text.sections[0].value = cue.text();
+    } else if let Ok(panel) = panel_query.get_single() {
+        let mut lines: Vec<_> = line_query
+            .iter()
+            .map(|(entity, line, _)| (entity, line.age))
+            .collect();
+        lines.sort_by(|a, b| b.1.total_cmp(&a.1));
+        for &(oldest, _) in lines
+            .iter()
+            .take((lines.len() + 1).saturating_sub(MAX_CAPTIONS))
+        {
+            commands.entity(oldest).despawn_recursive();
+        }
+        commands.entity(panel).with_children(|children| {
+            children.spawn((
+                Name::new("Caption"),
+                TextBundle {
+                    text: Text::from_section(
+                        cue.text(),
+                        TextStyle {
+                            font_size: ui_palette::LABEL_FONT_SIZE,
+                            color: ui_palette::BUTTON_TEXT,
+                            ..default()
+                        },
+                    ),
+                    style: Style {
+                        padding: UiRect::axes(Val::Px(10.0), Val::Px(2.0)),
+                        ..default()
+                    },
+                    background_color: ui_palette::NODE_BACKGROUND.with_alpha(0.6).into(),
+                    ..default()
+                },
+                ScaledText(ui_palette::LABEL_FONT_SIZE),
+                CaptionLine { cue, age: 0.0 },
+            ));
+        });
+    }
+
+    let Some(source) = source else {
+        return;
+    };
+    if let Some((_, mut marker, mut text)) = marker_query
+        .iter_mut()
+        .find(|(parent, ..)| parent.get() == source)
+    {
+        marker.age = 0.0;
+        text.sections[0].value = cue.marker();
+        return;
+    }
+    if let Some(mut clock) = commands.get_entity(source) {
+        clock.with_children(|children| {
+            children.spawn((
+                Name::new("Caption Marker"),
+                Text2dBundle {
+                    text: Text::from_section(
+                        cue.marker(),
+                        TextStyle {
+                            font_size: MARKER_FONT_SIZE,
+                            color: MARKER_COLOR,
+                            ..default()
+                        },
+                    ),
+                    transform: Transform::from_xyz(0.0, MARKER_Y, 50.0),
+                    ..default()
+                },
+                CaptionMarker { age: 0.0 },
+            ));
+        });
+    }
+}
+
+fn fade_captions(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut line_query: Query<(Entity, &mut CaptionLine, &mut Text, &mut BackgroundColor)>,
+) {
+    for (entity, mut line, mut text, mut background) in &mut line_query {
+        line.age += time.delta_seconds();
+        if line.age >= CAPTION_SECS {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        let alpha = ((CAPTION_SECS - line.age) / FADE_SECS).min(1.0);
+        text.sections[0].style.color.set_alpha(alpha);
+        background.0.set_alpha(0.6 * alpha);
+    }
+}
+
+fn fade_markers(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut marker_query: Query<(Entity, &mut CaptionMarker, &mut Text)>,
+) {
+    for (entity, mut marker, mut text) in &mut marker_query {
+        marker.age += time.delta_seconds();
+        if marker.age >= MARKER_SECS {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        let alpha = ((MARKER_SECS - marker.age) / FADE_SECS).min(1.0);
+        text.sections[0].style.color.set_alpha(alpha);
+    }
+}
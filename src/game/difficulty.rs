@@ -0,0 +1,72 @@
+//! The difficulty and mode picked on the title screen, and the rules the current run is played with.
+//! Custom rules are read from `difficulty.json` in the game's data directory.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::leaderboard::{storage, Difficulty, DifficultySettings, GameMode};
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(DifficultyChoice::load());
+    app.init_resource::<RunRules>();
+}
+
+const DIFFICULTY_KEY: &str = "difficulty";
+
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct DifficultyChoice {
+    pub difficulty: Difficulty,
+    /// Used when [`Difficulty::Custom`] is picked.
+    pub custom: DifficultySettings,
+    pub mode: GameMode,
+}
+
+impl DifficultyChoice {
+    fn load() -> Self {
+        let choice: Self = storage::load(DIFFICULTY_KEY).unwrap_or_default();
+        // Written back so there is a file to edit the custom rules in.
+        storage::save(DIFFICULTY_KEY, &choice);
+        choice
+    }
+
+    pub fn select(&mut self, difficulty: Difficulty) {
+        self.difficulty = difficulty;
+        storage::save(DIFFICULTY_KEY, self);
+    }
+
+    pub fn select_mode(&mut self, mode: GameMode) {
+        self.mode = mode;
+        storage::save(DIFFICULTY_KEY, self);
+    }
+
+    pub fn settings(&self) -> DifficultySettings {
+        self.difficulty
+            .preset()
+            .unwrap_or_else(|| self.custom.clone().custom())
+    }
+
+    pub fn rules(&self) -> RunRules {
+        RunRules {
+            difficulty: self.difficulty,
+            mode: self.mode,
+            settings: self.settings(),
+        }
+    }
+}
+
+/// The rules of the current run, fixed when it starts.
+#[derive(Resource, Debug, Clone, Default, Deref)]
+pub struct RunRules {
+    pub difficulty: Difficulty,
+    pub mode: GameMode,
+    #[deref]
+    pub settings: DifficultySettings,
+}
+
+impl RunRules {
+    /// Whether the run can go on the leaderboard.
+    pub fn ranked(&self) -> bool {
+        self.mode.ranked() && self.difficulty.ranked()
+    }
+}
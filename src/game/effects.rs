@@ -0,0 +1,351 @@
+//! Screen shake, particle bursts and hit-stop, to give game events some weight.
+//! Gameplay code triggers a [`PlayEffect`] next to the matching
+//! [`PlaySfx`](super::audio::sfx::PlaySfx), and every kind of effect
+//! can be turned off on the title screen for players who find them too much.
+
+use bevy::{prelude::*, utils::HashMap};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::accessibility::AccessibilitySettings;
+use crate::{leaderboard::storage, screen::Screen, AppSet};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<EffectKind>();
+    app.insert_resource(EffectsSettings::load());
+    app.init_resource::<Trauma>();
+    app.init_resource::<HitStop>();
+    app.init_resource::<BurstCooldowns>();
+    app.observe(play_effect);
+    app.add_systems(
+        Update,
+        (
+            update_hit_stop.in_set(AppSet::TickTimers),
+            (shake_camera, update_particles).in_set(AppSet::Update),
+        ),
+    );
+    app.add_systems(OnExit(Screen::Playing), reset_effects);
+}
+
+const EFFECTS_KEY: &str = "effects";
+
+/// How far the camera moves and turns at full trauma.
+const MAX_SHAKE_OFFSET: f32 = 14.0;
+const MAX_SHAKE_ANGLE: f32 = 0.025;
+/// Trauma lost per second.
+const TRAUMA_DECAY: f32 = 1.6;
+/// How fast the game runs during hit-stop.
+const HIT_STOP_SPEED: f32 = 0.05;
+const PARTICLE_Z: f32 = 400.0;
+
+#[derive(Event, Debug, Clone, Copy)]
+pub enum PlayEffect {
+    /// Add this much trauma, from 0 to 1, to the camera.
+    Shake(f32),
+    Burst(Burst, Vec3),
+    /// Nearly freeze the game for this many seconds.
+    HitStop(f32),
+}
+
+/// A named spray of particles.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Burst {
+    /// Drips of oil, falling.
+    OilDrip,
+    /// A puff of dust along the bench.
+    Dust,
+    /// Sparks flying off an overwound clock.
+    Sparks,
+}
+
+/// How the particles of a [`Burst`] look and move.
+struct BurstStyle {
+    count: usize,
+    color: Color,
+    /// Range to pick the size of each particle from.
+    size: (f32, f32),
+    /// Range to pick the starting speed from.
+    speed: (f32, f32),
+    /// The direction particles fly in, in radians from the right,
+    /// and how far either side of it they may go.
+    angle: f32,
+    spread: f32,
+    gravity: f32,
+    /// Fraction of speed lost per second.
+    drag: f32,
+    /// Range to pick how many seconds each particle lasts from.
+    lifetime: (f32, f32),
+    /// Minimum time in seconds between two bursts.
+    cooldown_secs: f32,
+}
+
+impl Burst {
+    fn style(self) -> BurstStyle {
+        use std::f32::consts::{FRAC_PI_2, PI};
+        match self {
+            Self::OilDrip => BurstStyle {
+                count: 2,
+                color: Color::srgb(0.2, 0.15, 0.08),
+                size: (4.0, 7.0),
+                speed: (10.0, 40.0),
+                angle: -FRAC_PI_2,
+                spread: 0.4,
+                gravity: 600.0,
+                drag: 0.0,
+                lifetime: (0.4, 0.7),
+                cooldown_secs: 0.15,
+            },
+            Self::Dust => BurstStyle {
+                count: 14,
+                color: Color::srgba(0.75, 0.68, 0.55, 0.7),
+                size: (6.0, 14.0),
+                speed: (60.0, 160.0),
+                angle: FRAC_PI_2,
+                spread: FRAC_PI_2,
+                gravity: -20.0,
+                drag: 3.0,
+                lifetime: (0.4, 0.8),
+                cooldown_secs: 0.0,
+            },
+            Self::Sparks => BurstStyle {
+                count: 4,
+                color: Color::srgb(1.0, 0.85, 0.3),
+                size: (2.0, 4.0),
+                speed: (150.0, 300.0),
+                angle: FRAC_PI_2,
+                spread: PI / 3.0,
+                gravity: 900.0,
+                drag: 1.0,
+                lifetime: (0.2, 0.4),
+                cooldown_secs: 0.08,
+            },
+        }
+    }
+}
+
+/// The effects a player can turn off.
+#[derive(Serialize, Deserialize, Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EffectKind {
+    Shake,
+    Particles,
+    HitStop,
+}
+
+impl EffectKind {
+    pub const ALL: [Self; 3] = [Self::Shake, Self::Particles, Self::HitStop];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Shake => "Shake",
+            Self::Particles => "Particles",
+            Self::HitStop => "Hit-stop",
+        }
+    }
+}
+
+#[derive(Resource, Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct EffectsSettings {
+    pub shake: bool,
+    pub particles: bool,
+    pub hit_stop: bool,
+}
+
+impl Default for EffectsSettings {
+    fn default() -> Self {
+        Self {
+            shake: true,
+            particles: true,
+            hit_stop: true,
+        }
+    }
+}
+
+impl EffectsSettings {
+    fn load() -> Self {
+        storage::load(EFFECTS_KEY).unwrap_or_default()
+    }
+
+    pub fn enabled(&self, kind: EffectKind) -> bool {
+        match kind {
+            EffectKind::Shake => self.shake,
+            EffectKind::Particles => self.particles,
+            EffectKind::HitStop => self.hit_stop,
+        }
+    }
+
+    pub fn toggle(&mut self, kind: EffectKind) {
+        let enabled = match kind {
+            EffectKind::Shake => &mut self.shake,
+            EffectKind::Particles => &mut self.particles,
+            EffectKind::HitStop => &mut self.hit_stop,
+        };
+        *enabled = !*enabled;
+        storage::save(EFFECTS_KEY, self);
+    }
+}
+
+/// How shaken up the camera is, from 0 to 1.
+#[derive(Resource, Debug, Default)]
+struct Trauma(f32);
+
+/// Real seconds of hit-stop left.
+#[derive(Resource, Debug, Default)]
+struct HitStop(f32);
+
+/// When each burst was last played, in seconds since startup.
+#[derive(Resource, Default, Deref, DerefMut)]
+struct BurstCooldowns(HashMap<Burst, f32>);
+
+#[derive(Component, Debug)]
+struct Particle {
+    velocity: Vec2,
+    gravity: f32,
+    drag: f32,
+    alpha: f32,
+    age: f32,
+    lifetime: f32,
+}
+
+fn play_effect(
+    trigger: Trigger<PlayEffect>,
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    settings: Res<EffectsSettings>,
+    accessibility: Res<AccessibilitySettings>,
+    mut trauma: ResMut<Trauma>,
+    mut hit_stop: ResMut<HitStop>,
+    mut cooldowns: ResMut<BurstCooldowns>,
+) {
+    match *trigger.event() {
+        PlayEffect::Shake(amount) => {
+            // Reduced motion wins over the shake setting.
+            if settings.shake && !accessibility.reduced_motion {
+                trauma.0 = (trauma.0 + amount).min(1.0);
+            }
+        }
+        PlayEffect::HitStop(secs) => {
+            if settings.hit_stop {
+                hit_stop.0 = hit_stop.0.max(secs);
+            }
+        }
+        PlayEffect::Burst(burst, position) => {
+            if !settings.particles {
+                return;
+            }
+            let style = burst.style();
+            let now = time.elapsed_seconds();
+            if let Some(last_played) = cooldowns.get(&burst) {
+                if now - last_played < style.cooldown_secs {
+                    return;
+                }
+            }
+            cooldowns.insert(burst, now);
+
+            let mut rng = rand::thread_rng();
+            for _ in 0..style.count {
+                let angle = style.angle + rng.gen_range(-style.spread..=style.spread);
+                let speed = rng.gen_range(style.speed.0..=style.speed.1);
+                let size = rng.gen_range(style.size.0..=style.size.1);
+                commands.spawn((
+                    Name::new("Particle"),
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: style.color,
+                            custom_size: Some(Vec2::splat(size)),
+                            ..default()
+                        },
+                        transform: Transform::from_translation(position.with_z(PARTICLE_Z)),
+                        ..default()
+                    },
+                    Particle {
+                        velocity: Vec2::from_angle(angle) * speed,
+                        gravity: style.gravity,
+                        drag: style.drag,
+                        alpha: style.color.alpha(),
+                        age: 0.0,
+                        lifetime: rng.gen_range(style.lifetime.0..=style.lifetime.1),
+                    },
+                    StateScoped(Screen::Playing),
+                ));
+            }
+        }
+    }
+}
+
+/// Hit-stop is timed in real seconds, since it slows down game time.
+fn update_hit_stop(
+    real_time: Res<Time<Real>>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut hit_stop: ResMut<HitStop>,
+) {
+    let speed = if hit_stop.0 > 0.0 {
+        hit_stop.0 -= real_time.delta_seconds();
+        HIT_STOP_SPEED
+    } else {
+        1.0
+    };
+    if virtual_time.relative_speed() != speed {
+        virtual_time.set_relative_speed(speed);
+    }
+}
+
+/// Shake is timed in real seconds, so it keeps going through hit-stop.
+fn shake_camera(
+    time: Res<Time<Real>>,
+    mut trauma: ResMut<Trauma>,
+    mut camera_query: Query<&mut Transform, With<Camera2d>>,
+) {
+    if trauma.0 <= 0.0 {
+        return;
+    }
+    trauma.0 = (trauma.0 - time.delta_seconds() * TRAUMA_DECAY).max(0.0);
+
+    let shake = trauma.0 * trauma.0;
+    let t = time.elapsed_seconds();
+    // Sines at unrelated frequencies, as cheap noise.
+    let noise = |seed: f32| (t * (37.0 + seed) + seed).sin() * (t * (23.0 + seed)).cos();
+    for mut transform in &mut camera_query {
+        transform.translation.x = MAX_SHAKE_OFFSET * shake * noise(1.0);
+        transform.translation.y = MAX_SHAKE_OFFSET * shake * noise(2.0);
+        transform.rotation = Quat::from_rotation_z(MAX_SHAKE_ANGLE * shake * noise(3.0));
+    }
+}
+
+fn update_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut particle_query: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>,
+) {
+    let dt = time.delta_seconds();
+    for (entity, mut particle, mut transform, mut sprite) in &mut particle_query {
+        particle.age += dt;
+        if particle.age >= particle.lifetime {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        let slowdown = (1.0 - particle.drag * dt).max(0.0);
+        particle.velocity *= slowdown;
+        particle.velocity.y -= particle.gravity * dt;
+        transform.translation += (particle.velocity * dt).extend(0.0);
+
+        let left = 1.0 - particle.age / particle.lifetime;
+        transform.scale = Vec3::splat(0.5 + 0.5 * left);
+        sprite.color.set_alpha(particle.alpha * left);
+    }
+}
+
+/// Leave the camera and game speed as they were when a run ends mid-effect.
+fn reset_effects(
+    mut trauma: ResMut<Trauma>,
+    mut hit_stop: ResMut<HitStop>,
+    mut camera_query: Query<&mut Transform, With<Camera2d>>,
+) {
+    trauma.0 = 0.0;
+    hit_stop.0 = 0.0;
+    for mut transform in &mut camera_query {
+        transform.translation.x = 0.0;
+        transform.translation.y = 0.0;
+        transform.rotation = Quat::IDENTITY;
+    }
+}
@@ -0,0 +1,231 @@
+//! The player's past runs, kept on this device.
+//! Every finished run is appended to a JSON Lines log, which can be exported
+//! as JSON or CSV for balancing with the `export_history` binary.
+
+use std::fmt::Write;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    difficulty::RunRules,
+    run::{run_in_progress, RunRecorder},
+    spawn::{
+        bench::is_versus,
+        clock::{ClockController, Interactable},
+        level::{RunDuration, Scoresource, SpawnLevel},
+        player::Player,
+    },
+    stats::RunStats,
+};
+use crate::{
+    leaderboard::{storage, DifficultySettings, RunInfo},
+    screen::{PlayingState, Screen},
+    AppSet,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<RunTimeline>();
+    app.observe(reset_timeline);
+    app.add_systems(
+        FixedUpdate,
+        sample_timeline
+            .after(AppSet::FixedUpdate)
+            .run_if(run_in_progress)
+            .run_if(in_state(Screen::Playing))
+            .run_if(in_state(PlayingState::Playing)),
+    );
+    // Versus runs aren't comparable with the rest.
+    app.add_systems(
+        OnEnter(PlayingState::GameOver),
+        record_run.run_if(not(is_versus)),
+    );
+}
+
+const HISTORY_KEY: &str = "run_history";
+/// Runs kept on web, where every run is saved with the rest in `localStorage`.
+const MAX_WEB_RUNS: usize = 50;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PastRun {
+    pub score: f32,
+    #[serde(flatten)]
+    pub run: RunInfo,
+    #[serde(default)]
+    pub seed: u64,
+    #[serde(default)]
+    pub settings: DifficultySettings,
+    pub stats: RunStats,
+    /// The state of the run at every whole second.
+    #[serde(default)]
+    pub timeline: Vec<TimelineSample>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct TimelineSample {
+    pub secs: u32,
+    pub score: f32,
+    pub oil_level: f32,
+    pub oil_leak: f32,
+    pub clocks: usize,
+}
+
+/// Finished runs, oldest first.
+#[derive(Debug, Default)]
+pub struct RunHistory(pub Vec<PastRun>);
+
+impl RunHistory {
+    pub fn load() -> Self {
+        Self(storage::load_lines(HISTORY_KEY))
+    }
+
+    /// Where the history is kept, for messages.
+    pub fn location() -> String {
+        storage::lines_location(HISTORY_KEY)
+    }
+
+    fn append(run: &PastRun) {
+        storage::append(HISTORY_KEY, run, MAX_WEB_RUNS);
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.0).unwrap_or_default()
+    }
+
+    /// One row per run, with a column for how long each clock was in sync.
+    pub fn to_csv(&self) -> String {
+        let max_clocks = self
+            .0
+            .iter()
+            .map(|past| past.stats.clocks.len())
+            .max()
+            .unwrap_or(0);
+
+        let mut csv = String::from(
+            "run,seed,date,version,mode,difficulty,score,duration_secs,peak_clocks,\
+             wind_secs,oil_drunk,run_downs,best_sync_streak_secs",
+        );
+        for clock in 1..=max_clocks {
+            let _ = write!(csv, ",clock{clock}_synced");
+        }
+        csv.push('\n');
+
+        for (index, past) in self.0.iter().enumerate() {
+            let stats = &past.stats;
+            let _ = write!(
+                csv,
+                "{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                index + 1,
+                past.seed,
+                past.run.date,
+                csv_field(&past.run.version),
+                past.run.mode.key(),
+                past.run.difficulty.key(),
+                past.score,
+                past.run.duration_secs,
+                stats.peak_clocks,
+                stats.wind_secs,
+                stats.oil_drunk,
+                stats.run_downs,
+                stats.best_sync_streak_secs,
+            );
+            for clock in 0..max_clocks {
+                csv.push(',');
+                if let Some(clock) = stats.clocks.get(clock) {
+                    let _ = write!(csv, "{}", clock.synced_fraction());
+                }
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// One row per second of every run.
+    pub fn timeline_csv(&self) -> String {
+        let mut csv =
+            String::from("run,seed,mode,difficulty,secs,score,oil_level,oil_leak,clocks\n");
+        for (index, past) in self.0.iter().enumerate() {
+            for sample in &past.timeline {
+                let _ = writeln!(
+                    csv,
+                    "{},{},{},{},{},{},{},{},{}",
+                    index + 1,
+                    past.seed,
+                    past.run.mode.key(),
+                    past.run.difficulty.key(),
+                    sample.secs,
+                    sample.score,
+                    sample.oil_level,
+                    sample.oil_leak,
+                    sample.clocks,
+                );
+            }
+        }
+        csv
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Samples of the current run, taken once per second.
+#[derive(Resource, Debug, Default)]
+struct RunTimeline {
+    samples: Vec<TimelineSample>,
+    next_secs: u32,
+}
+
+fn reset_timeline(_trigger: Trigger<SpawnLevel>, mut timeline: ResMut<RunTimeline>) {
+    *timeline = default();
+}
+
+fn sample_timeline(
+    run_duration: Res<RunDuration>,
+    scoresource: Res<Scoresource>,
+    player_query: Query<&ClockController, With<Player>>,
+    clock_query: Query<(), With<Interactable>>,
+    mut timeline: ResMut<RunTimeline>,
+) {
+    if run_duration.0 < timeline.next_secs as f32 {
+        return;
+    }
+    // In co-op, whoever is closest to running dry.
+    let Some(controller) = player_query
+        .iter()
+        .min_by(|a, b| a.oil_level.total_cmp(&b.oil_level))
+    else {
+        return;
+    };
+    let sample = TimelineSample {
+        secs: timeline.next_secs,
+        score: scoresource.0,
+        oil_level: controller.oil_level,
+        oil_leak: controller.oil_leak,
+        clocks: clock_query.iter().count(),
+    };
+    timeline.samples.push(sample);
+    timeline.next_secs += 1;
+}
+
+fn record_run(
+    scoresource: Res<Scoresource>,
+    run_duration: Res<RunDuration>,
+    stats: Res<RunStats>,
+    recorder: Res<RunRecorder>,
+    rules: Res<RunRules>,
+    timeline: Res<RunTimeline>,
+) {
+    RunHistory::append(&PastRun {
+        score: scoresource.0,
+        run: RunInfo::finished(rules.mode, rules.difficulty, run_duration.0),
+        seed: recorder.seed(),
+        settings: rules.settings.clone(),
+        stats: stats.clone(),
+        timeline: timeline.samples.clone(),
+    });
+}
@@ -2,18 +2,36 @@
 
 use bevy::prelude::*;
 
+pub mod accessibility;
 mod animation;
 pub mod assets;
 pub mod audio;
+pub mod captions;
+pub mod difficulty;
+pub mod effects;
+pub mod history;
 mod movement;
+pub mod run;
 pub mod spawn;
+pub mod stats;
+mod sync_feedback;
+mod wind_gauge;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
+        accessibility::plugin,
         animation::plugin,
         audio::plugin,
+        captions::plugin,
         assets::plugin,
+        difficulty::plugin,
+        effects::plugin,
+        history::plugin,
         movement::plugin,
+        run::plugin,
         spawn::plugin,
+        stats::plugin,
+        sync_feedback::plugin,
+        wind_gauge::plugin,
     ));
 }
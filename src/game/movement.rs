@@ -1,17 +1,26 @@
 //! Handle player input and translate it into movement.
-//! Note that the approach used here is simple for demonstration purposes.
-//! If you want to move the player in a smoother way,
-//! consider using a [fixed timestep](https://github.com/bevyengine/bevy/blob/latest/examples/movement/physics_in_fixed_timestep.rs).
+//! Runs on the fixed timestep from the recorded [`GameInput`], so that runs can be played back.
+//! The player and clocks change stations right away, and their sprites
+//! are tweened after them.
 
 use bevy::prelude::*;
 
 use super::{
-    assets::SfxKey,
-    audio::sfx::PlaySfx,
-    spawn::clock::{Clock, ClockController, ClockHandType, Interactable, Positions},
+    audio::{bank::SfxCue, sfx::PlaySfx},
+    effects::{Burst, PlayEffect},
+    run::GameInput,
+    spawn::{
+        bench::{Bench, Benches},
+        clock::{ClockController, Interactable, Station, CLOCK_RADIUS},
+        player::Player,
+    },
 };
 use crate::{
+    leaderboard::replay::{
+        can_put_down, walk, walk_direction, TickInput, OIL_STATION, SPAWN_STATION, STATIONS,
+    },
     screen::{PlayingState, Screen},
+    tween::{Ease, Tween, TweenTarget},
     AppSet,
 };
 
@@ -19,138 +28,166 @@ pub(super) fn plugin(app: &mut App) {
     // Record directional input as movement controls.
     app.register_type::<MovementController>();
     app.add_systems(
-        Update,
+        FixedUpdate,
         movement
             .in_set(AppSet::RecordInput)
             .run_if(in_state(Screen::Playing))
             .run_if(in_state(PlayingState::Playing)),
     );
+    app.add_systems(
+        Update,
+        (tween_player, tween_clocks)
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Playing)),
+    );
 }
 
+/// Seconds it takes to walk from one station to the next.
+const WALK_SECS: f32 = 0.15;
+/// Seconds it takes to lift or lower a clock.
+const LIFT_SECS: f32 = 0.1;
+/// How much higher a held clock is.
+const LIFT_HEIGHT: f32 = 30.0;
+const CLOCK_Z: f32 = 300.0;
+/// Held clocks go behind the ones on the bench.
+const HELD_CLOCK_Z: f32 = 200.0;
+
 #[derive(Component, Reflect, Default)]
 #[reflect(Component)]
 pub struct MovementController(pub Vec2);
 
 fn movement(
     mut commands: Commands,
-    input: Res<ButtonInput<KeyCode>>,
-    mut controller_query: Query<(&mut ClockController, &mut Transform), Without<Clock>>,
-    mut clocks: Query<(Entity, &mut Transform, &mut Clock, &Children), With<Interactable>>,
-    clock_children: Query<
-        &Transform,
-        (
-            With<ClockHandType>,
-            Without<Clock>,
-            Without<ClockController>,
-        ),
-    >,
-    positions: Res<Positions>,
+    input: Res<GameInput>,
+    benches: Res<Benches>,
+    mut controller_query: Query<(&Player, &Bench, &mut ClockController)>,
+    mut clocks: Query<(Entity, &Bench, &mut Station), With<Interactable>>,
 ) {
-    let mut intent = Vec2::ZERO;
-    if input.just_pressed(KeyCode::KeyA) || input.just_pressed(KeyCode::ArrowLeft) {
-        intent.x -= 1.0;
-    }
-    if input.just_pressed(KeyCode::KeyD) || input.just_pressed(KeyCode::ArrowRight) {
-        intent.x += 1.0;
-    }
-
-    let intent = intent.normalize_or_zero();
-
-    let (mut controller, mut transform) = controller_query.get_single_mut().unwrap();
-    controller.direction = intent;
-    if controller.index == 0 && controller.direction.x < 0.0 {
-        controller.index = 0;
-    } else {
-        controller.index = (controller.index as i32 + controller.direction.x as i32) as usize;
-        controller.index = controller.index.min(6);
-    }
-    let position = match controller.index {
-        0 => positions.clock_spawn,
-        1 => positions.clock_1,
-        2 => positions.clock_2,
-        3 => positions.clock_3,
-        4 => positions.clock_4,
-        5 => positions.clock_5,
-        6 => positions.oil_can,
-        _ => panic!("Invalid index"),
-    };
-
-    // pick up clock
-    if input.just_pressed(KeyCode::Space) && controller.index != 6 {
-        if controller.held_clock.is_some() {
-            if controller.index != 0 {
-                let clock_count = clocks
-                    .iter_mut()
-                    .filter(|(_, t, _, _)| t.translation.x == position.x)
-                    .count();
-                if clock_count == 1 {
-                    let clock = clocks
-                        .iter_mut()
-                        .find(|(e, _, _, _)| *e == controller.held_clock.unwrap());
-                    if clock.is_some() {
-                        let mut clock = clock.unwrap();
-                        clock.1.translation.y = position.y;
-                        clock.1.translation.z = 300.0;
-
-                        let hour = clock.3.first().unwrap();
-                        let mut hour_transform = *clock_children.get(*hour).unwrap();
-                        hour_transform.translation.z = 310.0;
-
-                        let minute = clock.3.get(1).unwrap();
-                        let mut minute_transform = *clock_children.get(*minute).unwrap();
-                        minute_transform.translation.z = 310.0;
-
+    // In player order, so the first player gets a clock both reach for, as in the replay.
+    let mut controllers: Vec<_> = controller_query.iter_mut().collect();
+    controllers.sort_by_key(|(player, ..)| player.0);
+
+    for current in 0..controllers.len() {
+        let input = input.player(controllers[current].0 .0);
+        let bench = *controllers[current].1;
+        let direction = walk_direction(input);
+
+        let held_by_others: Vec<Entity> = controllers
+            .iter()
+            .enumerate()
+            .filter(|&(other, _)| other != current)
+            .filter_map(|(_, (_, _, controller))| controller.held_clock)
+            .collect();
+        let controller = &mut controllers[current].2;
+        controller.direction = Vec2::new(direction, 0.0);
+        controller.index = walk(controller.index, direction);
+        let index = controller.index;
+
+        // pick up clock
+        if input.pressed(TickInput::ACTION) && index != OIL_STATION {
+            if controller.held_clock.is_some() {
+                if index != SPAWN_STATION {
+                    let clock_count = clocks
+                        .iter()
+                        .filter(|(_, on, station)| **on == bench && station.0 == index)
+                        .count();
+                    if can_put_down(index, clock_count) {
                         controller.held_clock = None;
+                        let feet = STATIONS[index] - Vec2::Y * CLOCK_RADIUS;
+                        commands.trigger(PlayEffect::Burst(
+                            Burst::Dust,
+                            benches.to_world(bench.0, feet.extend(0.0)),
+                        ));
+                        commands.trigger(PlayEffect::Shake(0.2));
+                        commands.trigger(PlayEffect::HitStop(0.04));
                     }
+                    commands.trigger(PlaySfx::Cue(SfxCue::ClockDown));
                 }
-                let r = rand::random::<f32>();
-                if r < 0.25 {
-                    commands.trigger(PlaySfx::Key(SfxKey::ClockDown1));
-                } else if r < 0.5 {
-                    commands.trigger(PlaySfx::Key(SfxKey::ClockDown2));
-                } else if r < 0.75 {
-                    commands.trigger(PlaySfx::Key(SfxKey::ClockDown3));
-                } else {
-                    commands.trigger(PlaySfx::Key(SfxKey::ClockDown4));
-                }
+            } else {
+                // Clocks the other player is carrying can't be taken off them.
+                controller.held_clock = clocks
+                    .iter()
+                    .find(|(entity, on, station)| {
+                        **on == bench && station.0 == index && !held_by_others.contains(entity)
+                    })
+                    .map(|(entity, ..)| entity);
             }
-        } else {
-            let target_clock = clocks
-                .iter_mut()
-                .find(|(_, t, _, _)| t.translation.x == position.x);
-            if target_clock.is_some() {
-                let mut clock = target_clock.unwrap();
-                clock.1.translation.y = position.y + 30.0;
-                clock.1.translation.z = 200.0;
-
-                let hour = clock.3.first().unwrap();
-                let mut hour_transform = *clock_children.get(*hour).unwrap();
-                hour_transform.translation.z = 210.0;
-
-                let minute = clock.3.get(1).unwrap();
-                let mut minute_transform = *clock_children.get(*minute).unwrap();
-                minute_transform.translation.z = 210.0;
+        }
 
-                controller.held_clock = Some(clock.0);
+        // move held clock
+        if controller.direction != Vec2::ZERO {
+            if let Some(held_clock) = controller.held_clock {
+                if let Ok((_, _, mut station)) = clocks.get_mut(held_clock) {
+                    station.0 = index;
+                }
             }
         }
     }
+}
 
-    // move and move held clock
-    if controller.direction != Vec2::ZERO {
-        transform.translation.x = position.x;
-        let held_clock: Option<(Entity, Mut<Transform>, Mut<Clock>, &Children)> =
-            clocks.iter_mut().find(|(e, _, _, _)| {
-                if controller.held_clock.is_some() {
-                    return controller.held_clock.unwrap() == *e;
-                }
-                false
-            });
+/// Walk the player to their station, with the [`MovementController`]
+/// pointing the way they're walking until they get there.
+fn tween_player(
+    mut player_query: Query<
+        (
+            &ClockController,
+            &Transform,
+            &mut Tween,
+            &mut MovementController,
+        ),
+        With<Player>,
+    >,
+) {
+    for (controller, transform, mut tween, mut movement) in &mut player_query {
+        let heading = tween
+            .heading()
+            .and_then(|target| target.translation)
+            .unwrap_or(transform.translation);
+        let x = STATIONS[controller.index].x;
+        if heading.x != x {
+            let target = heading.with_x(x);
+            tween.to(TweenTarget::translation(target), WALK_SECS, Ease::QuadInOut);
+        }
 
-        if held_clock.is_some() {
-            let mut clock = held_clock.unwrap();
-            clock.1.translation.x = transform.translation.x;
-            clock.1.translation.y = position.y + 30.0;
+        let dx = x - transform.translation.x;
+        let direction = if dx.abs() < 1.0 {
+            Vec2::ZERO
+        } else {
+            Vec2::new(dx.signum(), 0.0)
+        };
+        if movement.0 != direction {
+            movement.0 = direction;
         }
     }
 }
+
+fn tween_clocks(
+    controller_query: Query<&ClockController, With<Player>>,
+    mut clock_query: Query<(Entity, &Station, &Transform, &mut Tween)>,
+) {
+    let held_clocks: Vec<Entity> = controller_query
+        .iter()
+        .filter_map(|controller| controller.held_clock)
+        .collect();
+    for (entity, station, transform, mut tween) in &mut clock_query {
+        let position = STATIONS[station.0];
+        let target = if held_clocks.contains(&entity) {
+            Vec3::new(position.x, position.y + LIFT_HEIGHT, HELD_CLOCK_Z)
+        } else {
+            position.extend(CLOCK_Z)
+        };
+        let heading = tween
+            .heading()
+            .and_then(|target| target.translation)
+            .unwrap_or(transform.translation);
+        if heading == target {
+            continue;
+        }
+        let (secs, ease) = if heading.x != target.x {
+            (WALK_SECS, Ease::QuadInOut)
+        } else {
+            (LIFT_SECS, Ease::QuadOut)
+        };
+        tween.to(TweenTarget::translation(target), secs, ease);
+    }
+}
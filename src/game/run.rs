@@ -0,0 +1,200 @@
+//! Runs the game logic at a fixed rate from recorded inputs,
+//! so that a run can be played back to check its score.
+//! See [`crate::leaderboard::replay`] for the playback.
+
+use std::hash::Hash;
+
+use bevy::prelude::*;
+
+use super::{
+    difficulty::RunRules,
+    spawn::{clock::ClockController, player::Player},
+};
+use crate::{
+    leaderboard::{
+        replay::{InputLog, ScoreBreakdown, TickInput, TICK_HZ},
+        DifficultySettings, GameMode, RunSummary,
+    },
+    screen::{PlayingState, Screen},
+    AppSet,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(Time::<Fixed>::from_hz(TICK_HZ));
+    app.init_resource::<GameInput>();
+    app.init_resource::<RunRecorder>();
+
+    // Nothing happens after the oil runs out, even if the frame still has ticks left.
+    app.configure_sets(
+        FixedUpdate,
+        (AppSet::RecordInput, AppSet::Update, AppSet::FixedUpdate).run_if(run_in_progress),
+    );
+    app.add_systems(
+        Update,
+        record_input
+            .in_set(AppSet::RecordInput)
+            .run_if(in_state(Screen::Playing))
+            .run_if(in_state(PlayingState::Playing)),
+    );
+    app.add_systems(
+        FixedUpdate,
+        log_tick
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Playing))
+            .run_if(in_state(PlayingState::Playing)),
+    );
+}
+
+type Bindings<T> = [(u8, &'static [T]); 5];
+
+/// The keys behind each button of [`TickInput`].
+const KEY_BINDINGS: Bindings<KeyCode> = [
+    (TickInput::LEFT, &[KeyCode::KeyA, KeyCode::ArrowLeft]),
+    (TickInput::RIGHT, &[KeyCode::KeyD, KeyCode::ArrowRight]),
+    (TickInput::WIND, &[KeyCode::KeyW, KeyCode::ArrowUp]),
+    (TickInput::SET, &[KeyCode::KeyS, KeyCode::ArrowDown]),
+    (TickInput::ACTION, &[KeyCode::Space]),
+];
+
+/// With two players, the first has the left half of the keyboard and the second the right.
+const TWO_PLAYER_KEY_BINDINGS: [Bindings<KeyCode>; 2] = [
+    [
+        (TickInput::LEFT, &[KeyCode::KeyA]),
+        (TickInput::RIGHT, &[KeyCode::KeyD]),
+        (TickInput::WIND, &[KeyCode::KeyW]),
+        (TickInput::SET, &[KeyCode::KeyS]),
+        (TickInput::ACTION, &[KeyCode::Space]),
+    ],
+    [
+        (TickInput::LEFT, &[KeyCode::ArrowLeft]),
+        (TickInput::RIGHT, &[KeyCode::ArrowRight]),
+        (TickInput::WIND, &[KeyCode::ArrowUp]),
+        (TickInput::SET, &[KeyCode::ArrowDown]),
+        (TickInput::ACTION, &[KeyCode::Enter, KeyCode::ShiftRight]),
+    ],
+];
+
+/// Gamepads play as the last player: the only one alone, the second in co-op.
+const GAMEPAD_BINDINGS: Bindings<GamepadButtonType> = [
+    (TickInput::LEFT, &[GamepadButtonType::DPadLeft]),
+    (TickInput::RIGHT, &[GamepadButtonType::DPadRight]),
+    (
+        TickInput::WIND,
+        &[GamepadButtonType::DPadUp, GamepadButtonType::North],
+    ),
+    (
+        TickInput::SET,
+        &[GamepadButtonType::DPadDown, GamepadButtonType::West],
+    ),
+    (
+        TickInput::ACTION,
+        &[GamepadButtonType::South, GamepadButtonType::East],
+    ),
+];
+
+/// Each player's input for the next tick, collected every frame.
+#[derive(Resource, Debug, Default)]
+pub struct GameInput(pub Vec<TickInput>);
+
+impl GameInput {
+    pub fn new(players: usize) -> Self {
+        Self(vec![TickInput::default(); players])
+    }
+
+    pub fn player(&self, player: usize) -> TickInput {
+        self.0.get(player).copied().unwrap_or_default()
+    }
+}
+
+/// What the current run needs for its [`RunSummary`].
+#[derive(Resource, Debug, Default)]
+pub struct RunRecorder {
+    seed: u64,
+    /// One log per player.
+    logs: Vec<InputLog>,
+    pub breakdown: ScoreBreakdown,
+}
+
+impl RunRecorder {
+    pub fn start(&mut self, players: usize) {
+        *self = Self {
+            seed: rand::random(),
+            logs: vec![InputLog::default(); players],
+            ..default()
+        };
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn summary(&self, settings: &DifficultySettings) -> RunSummary {
+        let mut logs = self.logs.iter().cloned();
+        RunSummary::new(
+            self.seed,
+            settings.clone(),
+            self.breakdown,
+            logs.next().unwrap_or_default(),
+            logs.collect(),
+        )
+    }
+}
+
+/// Whether every player still has oil left.
+pub(crate) fn run_in_progress(player_query: Query<&ClockController, With<Player>>) -> bool {
+    !player_query.is_empty()
+        && player_query
+            .iter()
+            .all(|controller| controller.oil_level > 0.0)
+}
+
+fn record_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    buttons: Res<ButtonInput<GamepadButton>>,
+    gamepads: Res<Gamepads>,
+    rules: Res<RunRules>,
+    mut game_input: ResMut<GameInput>,
+) {
+    let players = game_input.0.len();
+    for (player, input) in game_input.0.iter_mut().enumerate() {
+        input.held = 0;
+        let key_bindings = match rules.mode {
+            GameMode::Solo => &KEY_BINDINGS,
+            GameMode::Coop | GameMode::Versus => &TWO_PLAYER_KEY_BINDINGS[player],
+        };
+        for &(button, codes) in key_bindings {
+            record_button(input, button, &keys, codes.iter().copied());
+        }
+        if player + 1 == players {
+            for gamepad in gamepads.iter() {
+                for &(button, types) in &GAMEPAD_BINDINGS {
+                    let codes = types.iter().map(|&kind| GamepadButton::new(gamepad, kind));
+                    record_button(input, button, &buttons, codes);
+                }
+            }
+        }
+    }
+}
+
+/// Record `button` as held and pressed if any of `codes` are.
+fn record_button<T: Copy + Eq + Hash + Send + Sync>(
+    tick_input: &mut TickInput,
+    button: u8,
+    input: &ButtonInput<T>,
+    codes: impl Iterator<Item = T> + Clone,
+) {
+    if input.any_pressed(codes.clone()) {
+        tick_input.held |= button;
+    }
+    // Kept until a tick has seen them.
+    if input.any_just_pressed(codes) {
+        tick_input.pressed |= button;
+    }
+}
+
+fn log_tick(mut game_input: ResMut<GameInput>, mut recorder: ResMut<RunRecorder>) {
+    for (log, input) in recorder.logs.iter_mut().zip(&mut game_input.0) {
+        log.push(*input);
+        input.pressed = 0;
+    }
+}
@@ -0,0 +1,99 @@
+//! The benches the game is played on. Solo and co-op runs share one bench,
+//! while in versus each player gets their own half-size bench, side by side.
+//! Everything on a bench is a child of its root, so stations stay in bench space.
+
+use bevy::prelude::*;
+
+use crate::{game::difficulty::RunRules, leaderboard::GameMode, screen::Screen};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Bench>();
+    app.init_resource::<Benches>();
+}
+
+/// How big each bench is in versus, and how far from the middle.
+const VERSUS_SCALE: f32 = 0.5;
+const VERSUS_OFFSET: Vec2 = Vec2::new(320.0, -60.0);
+
+/// Which bench this is on, from 0.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Bench(pub usize);
+
+/// The points scored on a bench, kept on its root.
+#[derive(Component, Debug, Default)]
+pub struct Score {
+    pub points: f32,
+    /// How many sabotages the points have earned so far, in versus.
+    pub sabotages: u32,
+}
+
+/// The root of every bench in the current run, with where it's placed.
+#[derive(Resource, Debug, Default)]
+pub struct Benches(Vec<(Entity, Transform)>);
+
+impl Benches {
+    pub fn spawn(commands: &mut Commands, mode: GameMode) -> Self {
+        let count = bench_count(mode);
+        Self(
+            (0..count)
+                .map(|bench| {
+                    let transform = if count == 1 {
+                        Transform::IDENTITY
+                    } else {
+                        let side = if bench == 0 { -1.0 } else { 1.0 };
+                        Transform::from_translation(
+                            (VERSUS_OFFSET * Vec2::new(side, 1.0)).extend(0.0),
+                        )
+                        .with_scale(Vec3::splat(VERSUS_SCALE))
+                    };
+                    let root = commands
+                        .spawn((
+                            Name::new("Bench"),
+                            SpatialBundle::from_transform(transform),
+                            Bench(bench),
+                            Score::default(),
+                            StateScoped(Screen::Playing),
+                        ))
+                        .id();
+                    (root, transform)
+                })
+                .collect(),
+        )
+    }
+
+    pub fn count(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn root(&self, bench: usize) -> Entity {
+        self.0[bench].0
+    }
+
+    /// Where a point on a bench ends up on screen.
+    pub fn to_world(&self, bench: usize, local: Vec3) -> Vec3 {
+        self.0
+            .get(bench)
+            .map_or(local, |(_, transform)| transform.transform_point(local))
+    }
+}
+
+pub fn bench_count(mode: GameMode) -> usize {
+    match mode {
+        GameMode::Versus => 2,
+        _ => 1,
+    }
+}
+
+/// Which bench a player plays on.
+pub fn player_bench(mode: GameMode, player: usize) -> usize {
+    match mode {
+        GameMode::Versus => player,
+        _ => 0,
+    }
+}
+
+/// Whether the current run is a versus match.
+pub(crate) fn is_versus(rules: Res<RunRules>) -> bool {
+    rules.mode == GameMode::Versus
+}
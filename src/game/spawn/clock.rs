@@ -3,17 +3,41 @@ use bevy::{prelude::*, sprite::Anchor};
 use crate::{
     game::{
         assets::{HandleMap, ImageKey, SfxKey},
-        audio::sfx::{PlayLoopingSfx, PlaySfx, StopLoopingSfx},
+        audio::{
+            bank::SfxCue,
+            sfx::{PlayLoopingSfx, PlaySfx, StopLoopingSfx},
+            ticking::{ClockTicker, TickRate, TickSynth, TickTimbre},
+        },
+        captions::{Caption, CaptionCue},
+        difficulty::RunRules,
+        effects::{Burst, PlayEffect},
+        run::{GameInput, RunRecorder},
+    },
+    leaderboard::{
+        replay::{
+            clock_hold_secs, in_sync, run_down, run_turn, score_clock, set_turn, track_hold, wind,
+            TickInput, OIL_STATION, SPAWN_STATION, STATIONS,
+        },
+        GameMode,
     },
     screen::{PlayingState, Screen},
+    tween::Tween,
     AppSet,
 };
 
-use super::level::{Score, Scoresource};
+use super::{
+    bench::{Bench, Benches, Score},
+    level::Scoresource,
+    player::Player,
+};
 
 pub(super) fn plugin(app: &mut App) {
+    app.add_event::<ClockScored>();
+    app.add_event::<ClockRanDown>();
+    app.add_event::<ClockWound>();
     app.observe(spawn_interact_clock);
     app.observe(spawn_main_clock);
+    app.observe(sabotage);
     app.add_systems(
         FixedUpdate,
         (tick_clocks, score_clocks)
@@ -23,7 +47,7 @@ pub(super) fn plugin(app: &mut App) {
             .run_if(in_state(PlayingState::Playing)),
     );
     app.add_systems(
-        Update,
+        FixedUpdate,
         record_clock_controller
             .in_set(AppSet::RecordInput)
             .run_if(in_state(Screen::Playing))
@@ -37,52 +61,131 @@ pub(super) fn plugin(app: &mut App) {
             .run_if(in_state(PlayingState::Playing)),
     );
     app.insert_resource(Positions {
-        clock_spawn: Vec2::new(-550.0, -185.0),
-        clock_1: Vec2::new(-330.0, -220.0),
-        clock_2: Vec2::new(-180.0, -220.0),
-        clock_3: Vec2::new(-30.0, -220.0),
-        clock_4: Vec2::new(120.0, -220.0),
-        clock_5: Vec2::new(270.0, -220.0),
-        oil_can: Vec2::new(550.0, -200.0),
+        clock_spawn: STATIONS[SPAWN_STATION],
+        oil_can: STATIONS[OIL_STATION],
     });
 
     app.insert_resource(Clocks {
         clocks: vec![
             ClockData {
                 time_left: 0.0,
-                audio: SfxKey::Ticking2,
+                timbre: TickTimbre {
+                    tick_hz: 2200.0,
+                    tock_hz: 1800.0,
+                    decay: 90.0,
+                    noise: 0.35,
+                    volume: 0.5,
+                },
             },
             ClockData {
                 time_left: 0.0,
-                audio: SfxKey::Ticking3,
+                timbre: TickTimbre {
+                    tick_hz: 2600.0,
+                    tock_hz: 2100.0,
+                    decay: 110.0,
+                    noise: 0.3,
+                    volume: 0.5,
+                },
             },
             ClockData {
                 time_left: 0.0,
-                audio: SfxKey::Ticking4,
+                timbre: TickTimbre {
+                    tick_hz: 1900.0,
+                    tock_hz: 1500.0,
+                    decay: 80.0,
+                    noise: 0.4,
+                    volume: 0.5,
+                },
             },
             ClockData {
                 time_left: 0.0,
-                audio: SfxKey::Ticking5,
+                timbre: TickTimbre {
+                    tick_hz: 3000.0,
+                    tock_hz: 2500.0,
+                    decay: 130.0,
+                    noise: 0.25,
+                    volume: 0.5,
+                },
             },
             ClockData {
                 time_left: 0.0,
-                audio: SfxKey::Ticking6,
+                timbre: TickTimbre {
+                    tick_hz: 2400.0,
+                    tock_hz: 1650.0,
+                    decay: 70.0,
+                    noise: 0.45,
+                    volume: 0.5,
+                },
             },
         ],
     });
 }
 
+/// The main clock ticks slower and deeper than the bench clocks.
+const MAIN_CLOCK_TIMBRE: TickTimbre = TickTimbre {
+    tick_hz: 900.0,
+    tock_hz: 700.0,
+    decay: 45.0,
+    noise: 0.2,
+    volume: 0.7,
+};
+
+/// Seconds of winding a clock can hold. Winding past this overwinds it.
+pub const FULL_WIND_SECS: f32 = 30.0;
+/// Half the size of a bench clock sprite.
+pub const CLOCK_RADIUS: f32 = 64.0;
+
+/// In versus, every time a player scores this many points, their opponent is sent a drifting clock.
+const SABOTAGE_POINTS: f32 = 75.0;
+/// How much faster the hands of a drifting clock go.
+const DRIFT_RATE: f32 = 1.05;
+/// Drifting clocks arrive already wound for this long, so they can't be left alone.
+const DRIFT_WIND_SECS: f32 = 20.0;
+/// The face tint of a running drifting clock, set by the wind gauge with the other face colours.
+pub const DRIFT_TINT: Color = Color::srgb(1.0, 0.7, 0.65);
+
+#[derive(Event, Debug)]
+pub struct SpawnClock {
+    pub bench: usize,
+    /// Sent by the other player, see [`Drifting`].
+    pub drifting: bool,
+}
+
 #[derive(Event, Debug)]
-pub struct SpawnClock;
+pub struct SpawnMainClock(pub usize);
 
+/// Sent when a player in versus earns a sabotage against the other bench.
 #[derive(Event, Debug)]
-pub struct SpawnMainClock;
+pub struct Sabotage {
+    pub from: usize,
+}
+
+/// A clock that runs fast, and takes points away while it's out of sync.
+#[derive(Component, Debug)]
+pub struct Drifting;
+
+/// Sent every tick for each running clock as it is scored.
+#[derive(Event, Debug)]
+pub struct ClockScored {
+    pub clock: Entity,
+    /// Whether it was in sync with the main clock.
+    pub synced: bool,
+    /// How far, in radians, the further off of its hands is from the main clock's.
+    pub offset: f32,
+}
+
+/// Sent when a clock runs out of time.
+#[derive(Event, Debug)]
+pub struct ClockRanDown(pub Entity);
+
+/// Sent every tick the held clock is being wound.
+#[derive(Event, Debug)]
+pub struct ClockWound;
 
 #[derive(Component)]
 pub struct Clock {
     pub is_main: bool,
     pub time_left: f32,
-    pub audio: SfxKey,
 }
 
 #[derive(Component)]
@@ -103,22 +206,23 @@ pub struct ClockController {
     pub direction: Vec2,
     pub oil_level: f32,
     pub oil_leak: f32,
+    /// Seconds the oil has been leaking for.
+    pub leak_secs: f32,
 }
 
 #[derive(Resource)]
 pub struct Positions {
     pub clock_spawn: Vec2,
-    pub clock_1: Vec2,
-    pub clock_2: Vec2,
-    pub clock_3: Vec2,
-    pub clock_4: Vec2,
-    pub clock_5: Vec2,
     pub oil_can: Vec2,
 }
 
 #[derive(Component)]
 pub struct Interactable;
 
+/// Which of the [`STATIONS`] a bench clock stands at.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Station(pub usize);
+
 #[derive(Resource)]
 pub struct Clocks {
     clocks: Vec<ClockData>,
@@ -126,155 +230,167 @@ pub struct Clocks {
 
 pub struct ClockData {
     pub time_left: f32,
-    pub audio: SfxKey,
+    pub timbre: TickTimbre,
 }
 
 fn record_clock_controller(
     time: Res<Time>,
-    input: Res<ButtonInput<KeyCode>>,
-    mut controller_query: Query<&mut ClockController>,
+    input: Res<GameInput>,
+    mut controller_query: Query<(&Player, &mut ClockController)>,
 ) {
-    for mut controller in &mut controller_query {
-        if input.pressed(KeyCode::KeyS) || input.pressed(KeyCode::ArrowDown) {
-            if controller.setting {
-                controller.time_setting += time.delta_seconds();
-            } else {
-                controller.setting = true;
-                controller.time_setting = 0.0;
-            }
-        } else {
-            controller.setting = false;
-            controller.time_setting = 0.0;
-        }
-
-        if input.pressed(KeyCode::KeyW) || input.pressed(KeyCode::ArrowUp) {
-            if controller.winding {
-                controller.time_winding += time.delta_seconds();
-            } else {
-                controller.winding = true;
-                controller.time_winding = 0.0;
-            }
-        } else {
-            controller.winding = false;
-            controller.time_winding = 0.0;
-        }
+    let dt = time.delta_seconds();
+    for (player, mut controller) in &mut controller_query {
+        let input = input.player(player.0);
+        let controller = &mut *controller;
+        let set = input.held(TickInput::SET);
+        track_hold(
+            &mut controller.setting,
+            &mut controller.time_setting,
+            set,
+            dt,
+        );
+        let wound = input.held(TickInput::WIND);
+        track_hold(
+            &mut controller.winding,
+            &mut controller.time_winding,
+            wound,
+            dt,
+        );
     }
 }
 
 fn apply_clock_control(
     time: Res<Time>,
     mut commands: Commands,
+    mut ev_wound: EventWriter<ClockWound>,
     mut control_query: Query<&mut ClockController, Without<Interactable>>,
-    mut clocks: Query<(Entity, &mut Clock, &Transform, &Children), With<Interactable>>,
+    mut clocks: Query<(Entity, &mut Clock, &GlobalTransform, &Children), With<Interactable>>,
     mut q_child: Query<
         (&mut Transform, &ClockHandType),
         (Without<Interactable>, Without<ClockController>),
     >,
+    // The setting speed captioned last.
+    mut last_speed: Local<Option<u8>>,
 ) {
-    let result = control_query.get_single_mut();
-    if result.is_err() {
-        return;
-    }
-    let mut controller = result.unwrap();
-    if controller.held_clock.is_none() {
-        return;
-    }
+    let mut anyone_winding = false;
+    // How long the longest held set button has been held, if any are, and on which clock.
+    let mut setting_for: Option<(f32, Entity)> = None;
+    for mut controller in &mut control_query {
+        let Some(held_clock) = controller.held_clock else {
+            continue;
+        };
 
-    if controller.winding {
-        controller.time_winding += time.delta_seconds();
-        controller.time_winding = controller.time_winding.min(3.0);
-    }
+        if controller.winding {
+            controller.time_winding =
+                clock_hold_secs(controller.time_winding, time.delta_seconds());
+        }
 
-    if controller.setting {
-        controller.time_setting += time.delta_seconds();
-        controller.time_setting = controller.time_setting.min(3.0);
-    }
+        if controller.setting {
+            controller.time_setting =
+                clock_hold_secs(controller.time_setting, time.delta_seconds());
+        }
 
-    let children = clocks
-        .iter_mut()
-        .find(|t| controller.held_clock.is_some() && t.0 == controller.held_clock.unwrap());
-    if children.is_none() {
-        return;
-    }
-    let mut children = children.unwrap();
+        let Ok((_, mut clock, transform, children)) = clocks.get_mut(held_clock) else {
+            continue;
+        };
+
+        if controller.winding {
+            anyone_winding = true;
+            clock.time_left = wind(clock.time_left, time.delta_seconds());
+            ev_wound.send(ClockWound);
+            if clock.time_left > FULL_WIND_SECS {
+                commands.trigger(PlayEffect::Burst(
+                    Burst::Sparks,
+                    transform.translation() + Vec3::Y * CLOCK_RADIUS,
+                ));
+            }
+        }
 
-    if controller.winding {
-        let zero = children.1.time_left == 0.0;
-        if zero {
-            commands.trigger(PlayLoopingSfx::Key(children.1.audio));
+        if controller.setting {
+            if setting_for.is_none_or(|(secs, _)| controller.time_setting > secs) {
+                setting_for = Some((controller.time_setting, held_clock));
+            }
+            let (hour, minute) = set_turn(controller.time_setting, time.delta_seconds());
+            for &child in children.iter() {
+                if let Ok((mut transform, hand_type)) = q_child.get_mut(child) {
+                    match hand_type {
+                        ClockHandType::Hour => transform.rotate_z(hour),
+                        ClockHandType::Minute => transform.rotate_z(minute),
+                    }
+                }
+            }
         }
-        children.1.time_left += time.delta_seconds() * 6.0;
+    }
+
+    if anyone_winding {
         commands.trigger(PlayLoopingSfx::Key(SfxKey::Setting1));
     } else {
         commands.trigger(StopLoopingSfx::Key(SfxKey::Setting1));
     }
 
-    if controller.setting {
-        for &child in children.3.iter() {
-            let child_result = q_child.get_mut(child);
-
-            match controller.time_setting {
-                0.0..=0.7 => commands.trigger(PlayLoopingSfx::Key(SfxKey::Setting1)),
-                0.7..=1.4 => {
-                    commands.trigger(StopLoopingSfx::Key(SfxKey::Setting1));
-                    commands.trigger(PlayLoopingSfx::Key(SfxKey::Setting2))
-                }
-                1.4..=2.1 => {
-                    commands.trigger(StopLoopingSfx::Key(SfxKey::Setting2));
-                    commands.trigger(PlayLoopingSfx::Key(SfxKey::Setting3))
-                }
-                2.1..=3.0 => {
-                    commands.trigger(StopLoopingSfx::Key(SfxKey::Setting3));
-                    commands.trigger(PlayLoopingSfx::Key(SfxKey::Setting4))
-                }
-                _ => {}
-            }
+    let speed = setting_for.map(|(secs, clock)| (setting_speed(secs), clock));
+    if speed.map(|(speed, _)| speed) != *last_speed {
+        *last_speed = speed.map(|(speed, _)| speed);
+        if let Some((speed, clock)) = speed {
+            commands.trigger(Caption {
+                cue: CaptionCue::SettingSpeed(speed),
+                source: Some(clock),
+            });
+        }
+    }
 
-            if let Ok((mut transform, hand_type)) = child_result {
-                match hand_type {
-                    ClockHandType::Hour => {
-                        transform.rotate_z(
-                            time.delta_seconds() * -0.008726646 * 100.0 * controller.time_setting,
-                        );
-                    }
-                    ClockHandType::Minute => {
-                        transform.rotate_z(
-                            time.delta_seconds() * -0.1047198 * 100.0 * controller.time_setting,
-                        );
-                    }
-                }
-            }
+    match speed.map(|(speed, _)| speed) {
+        Some(1) => commands.trigger(PlayLoopingSfx::Key(SfxKey::Setting1)),
+        Some(2) => {
+            commands.trigger(StopLoopingSfx::Key(SfxKey::Setting1));
+            commands.trigger(PlayLoopingSfx::Key(SfxKey::Setting2))
+        }
+        Some(3) => {
+            commands.trigger(StopLoopingSfx::Key(SfxKey::Setting2));
+            commands.trigger(PlayLoopingSfx::Key(SfxKey::Setting3))
+        }
+        Some(_) => {
+            commands.trigger(StopLoopingSfx::Key(SfxKey::Setting3));
+            commands.trigger(PlayLoopingSfx::Key(SfxKey::Setting4))
+        }
+        None => {
+            // commands.trigger(StopLoopingSfx::Key(SfxKey::Setting1));
+            commands.trigger(StopLoopingSfx::Key(SfxKey::Setting2));
+            commands.trigger(StopLoopingSfx::Key(SfxKey::Setting3));
+            commands.trigger(StopLoopingSfx::Key(SfxKey::Setting4));
+            commands.trigger(StopLoopingSfx::Key(SfxKey::Setting5));
+            commands.trigger(StopLoopingSfx::Key(SfxKey::Setting6));
         }
-    } else {
-        // commands.trigger(StopLoopingSfx::Key(SfxKey::Setting1));
-        commands.trigger(StopLoopingSfx::Key(SfxKey::Setting2));
-        commands.trigger(StopLoopingSfx::Key(SfxKey::Setting3));
-        commands.trigger(StopLoopingSfx::Key(SfxKey::Setting4));
-        commands.trigger(StopLoopingSfx::Key(SfxKey::Setting5));
-        commands.trigger(StopLoopingSfx::Key(SfxKey::Setting6));
+    }
+}
+
+/// Which of the four setting sounds plays after holding set for `secs`.
+fn setting_speed(secs: f32) -> u8 {
+    match secs {
+        secs if secs <= 0.7 => 1,
+        secs if secs <= 1.4 => 2,
+        secs if secs <= 2.1 => 3,
+        _ => 4,
     }
 }
 
 fn tick_clocks(
-    mut commands: Commands,
     time: Res<Time>,
-    mut q_parent: Query<(&mut Clock, &Children)>,
+    mut ev_ran_down: EventWriter<ClockRanDown>,
+    mut q_parent: Query<(Entity, &mut Clock, &Children, Has<Drifting>)>,
     mut q_child: Query<(&mut Transform, &ClockHandType), Without<Clock>>,
 ) {
-    let hour_speed = time.delta_seconds() * -0.008726646 * 2.0;
-    let minute_speed = time.delta_seconds() * -0.1047198 * 2.0;
-    for (mut clock, children) in q_parent.iter_mut() {
+    for (entity, mut clock, children, drifting) in q_parent.iter_mut() {
+        let rate = if drifting { DRIFT_RATE } else { 1.0 };
+        let (hour_speed, minute_speed) = run_turn(rate, time.delta_seconds());
         if !clock.is_main {
-            let above = clock.time_left > 0.0;
-
-            clock.time_left -= time.delta_seconds();
-            clock.time_left = clock.time_left.max(0.0);
-
-            if above && clock.time_left == 0.0 {
-                commands.trigger(StopLoopingSfx::Key(clock.audio));
-            }
+            let running = clock.time_left > 0.0;
+            clock.time_left = run_down(clock.time_left, time.delta_seconds());
 
             if clock.time_left == 0.0 {
+                if running {
+                    ev_ran_down.send(ClockRanDown(entity));
+                }
                 continue;
             }
         }
@@ -296,61 +412,110 @@ fn tick_clocks(
     }
 }
 
+/// Every clock is scored against the main clock on its own bench.
 fn score_clocks(
     mut commands: Commands,
     time: Res<Time>,
-    mut score: Query<(&mut Score, &mut Text)>,
-    clocks: Query<(&Clock, &Children)>,
+    clocks: Query<(Entity, &Clock, &Bench, &Children, Has<Drifting>)>,
     clock_children: Query<(&Transform, &ClockHandType)>,
+    mut bench_query: Query<(&Bench, &mut Score)>,
     mut scoresource: ResMut<Scoresource>,
+    mut recorder: ResMut<RunRecorder>,
+    rules: Res<RunRules>,
+    mut ev_scored: EventWriter<ClockScored>,
 ) {
-    let main = clocks.iter().find(|(clock, _)| clock.is_main).unwrap();
-    let main_rotations = get_clock_rotations(main.1, &clock_children);
-    let (mut score, mut text) = score.single_mut();
-
-    for (clock, children) in clocks.iter() {
-        if clock.is_main || clock.time_left <= 0.0 {
+    let points = time.delta_seconds() * rules.score_multiplier;
+
+    for (bench, mut score) in &mut bench_query {
+        let on_bench: Vec<_> = clocks
+            .iter()
+            .filter(|(_, _, on, ..)| *on == bench)
+            .collect();
+        let Some(main) = on_bench.iter().find(|(_, clock, ..)| clock.is_main) else {
             continue;
-        }
-        score.0 += 1.0 * time.delta_seconds();
+        };
+        let main_rotations = get_clock_rotations(main.3, &clock_children);
 
-        let clock_rotation = get_clock_rotations(children, &clock_children);
-
-        let hour_diff = main_rotations.hour.angle_between(clock_rotation.hour);
-        let minute_diff = main_rotations.minute.angle_between(clock_rotation.minute);
-
-        if hour_diff < 0.1 && minute_diff < 0.1 {
-            score.0 += 1.0 * time.delta_seconds();
-        }
-    }
-
-    let clock_count = clocks.iter().count() - 1;
-
-    match clock_count {
-        1 => {
-            if score.0 > 25.0 {
-                commands.trigger(SpawnClock);
+        let mut clock_count = 0;
+        for (entity, clock, _, children, drifting) in on_bench {
+            if clock.is_main {
+                continue;
             }
-        }
-        2 => {
-            if score.0 > 100.0 {
-                commands.trigger(SpawnClock);
+            clock_count += 1;
+            if clock.time_left <= 0.0 {
+                continue;
             }
+
+            let clock_rotation = get_clock_rotations(children, &clock_children);
+
+            let hour_diff = main_rotations.hour.angle_between(clock_rotation.hour);
+            let minute_diff = main_rotations.minute.angle_between(clock_rotation.minute);
+
+            let synced = in_sync(hour_diff, minute_diff, &rules);
+            let earned = score_clock(score.points, points, synced, drifting);
+            earned.add_to(&mut score.points);
+            earned.add_to(&mut scoresource.0);
+            recorder.breakdown += earned;
+            ev_scored.send(ClockScored {
+                clock: entity,
+                synced,
+                offset: hour_diff.max(minute_diff),
+            });
         }
-        3 => {
-            if score.0 > 250.0 {
-                commands.trigger(SpawnClock);
+
+        if rules.mode == GameMode::Versus {
+            let earned = (score.points / SABOTAGE_POINTS) as u32;
+            if earned > score.sabotages {
+                score.sabotages = earned;
+                commands.trigger(Sabotage { from: bench.0 });
             }
         }
-        4 => {
-            if score.0 > 500.0 {
-                commands.trigger(SpawnClock);
-            }
+
+        let threshold = rules.spawn_threshold(clock_count);
+        if threshold.is_some_and(|threshold| score.points > threshold) {
+            commands.trigger(SpawnClock {
+                bench: bench.0,
+                drifting: false,
+            });
         }
-        _ => {}
     }
-    text.sections[0].value = format!("{:.2}", score.0);
-    scoresource.0 = score.0;
+}
+
+/// Send the other bench a drifting clock or, if it has no room for one,
+/// set one of its clocks drifting.
+fn sabotage(
+    trigger: Trigger<Sabotage>,
+    mut commands: Commands,
+    benches: Res<Benches>,
+    rules: Res<RunRules>,
+    mut clocks: Query<(Entity, &Bench, &mut Clock, Has<Drifting>), With<Interactable>>,
+) {
+    let target = (trigger.event().from + 1) % benches.count();
+    let on_target = clocks
+        .iter()
+        .filter(|(_, bench, ..)| bench.0 == target)
+        .count();
+    if on_target < rules.max_clocks() {
+        commands.trigger(SpawnClock {
+            bench: target,
+            drifting: true,
+        });
+        return;
+    }
+
+    let Some((entity, _, mut clock, _)) = clocks
+        .iter_mut()
+        .find(|(_, bench, .., drifting)| bench.0 == target && !drifting)
+    else {
+        return;
+    };
+    commands.trigger(PlaySfx::Cue(SfxCue::ClockSpawn));
+    commands.trigger(Caption {
+        cue: CaptionCue::DriftingClock,
+        source: Some(entity),
+    });
+    clock.time_left = clock.time_left.max(DRIFT_WIND_SECS);
+    commands.entity(entity).insert(Drifting);
 }
 
 struct ClockRotations {
@@ -365,27 +530,26 @@ fn get_clock_rotations(
     let mut hour: Quat = Quat::IDENTITY;
     let mut minute: Quat = Quat::IDENTITY;
 
-    let hour_hand = clock_children.first().unwrap();
-    let hour_hand_result = all_children.get(*hour_hand);
-    if let Ok((transform, _)) = hour_hand_result {
-        hour = transform.rotation.normalize();
-    }
-
-    let minute_hand = clock_children.get(1).unwrap();
-    let minute_hand_result = all_children.get(*minute_hand);
-    if let Ok((transform, _)) = minute_hand_result {
-        minute = transform.rotation.normalize();
+    for &child in clock_children {
+        match all_children.get(child) {
+            Ok((transform, ClockHandType::Hour)) => hour = transform.rotation.normalize(),
+            Ok((transform, ClockHandType::Minute)) => minute = transform.rotation.normalize(),
+            Err(_) => {}
+        }
     }
 
     ClockRotations { hour, minute }
 }
 
 fn spawn_main_clock(
-    _trigger: Trigger<SpawnMainClock>,
+    trigger: Trigger<SpawnMainClock>,
+    benches: Res<Benches>,
     mut commands: Commands,
     image_handles: Res<HandleMap<ImageKey>>,
+    mut tick_synths: ResMut<Assets<TickSynth>>,
 ) {
-    commands.trigger(PlayLoopingSfx::Key(SfxKey::Ticking1));
+    let rate = TickRate::default();
+    let synth = tick_synths.add(TickSynth::new(rate.clone(), MAIN_CLOCK_TIMBRE));
     commands
         .spawn((
             Name::new("MainClock"),
@@ -404,10 +568,13 @@ fn spawn_main_clock(
             Clock {
                 is_main: true,
                 time_left: 0.0,
-                audio: SfxKey::Ticking1,
             },
-            StateScoped(Screen::Playing),
+            Bench(trigger.event().0),
+            ClockTicker::new(rate),
+            synth,
+            PlaybackSettings::LOOP,
         ))
+        .set_parent(benches.root(trigger.event().0))
         .with_children(|parent| {
             parent.spawn((
                 SpriteBundle {
@@ -444,51 +611,70 @@ fn spawn_main_clock(
 }
 
 fn spawn_interact_clock(
-    _trigger: Trigger<SpawnClock>,
+    trigger: Trigger<SpawnClock>,
+    benches: Res<Benches>,
     positions: Res<Positions>,
     clock_data: Res<Clocks>,
     mut commands: Commands,
     image_handles: Res<HandleMap<ImageKey>>,
-    clocks: Query<(&Clock, &Transform), With<Interactable>>,
+    mut tick_synths: ResMut<Assets<TickSynth>>,
+    clocks: Query<&Bench, With<Interactable>>,
 ) {
-    let r = rand::random::<f32>();
-    if r < 0.25 {
-        commands.trigger(PlaySfx::Key(SfxKey::ClockSpawn1));
-    } else if r < 0.5 {
-        commands.trigger(PlaySfx::Key(SfxKey::ClockSpawn2));
-    } else if r < 0.75 {
-        commands.trigger(PlaySfx::Key(SfxKey::ClockSpawn3));
-    } else {
-        commands.trigger(PlaySfx::Key(SfxKey::ClockSpawn4));
-    }
-    let clock_count = clocks.iter().count();
+    let &SpawnClock { bench, drifting } = trigger.event();
+    let clock_count = clocks.iter().filter(|on| on.0 == bench).count();
+    // A sabotage and a new clock can both be on their way to a nearly full bench.
+    let Some(clock_data) = clock_data.clocks.get(clock_count) else {
+        return;
+    };
+    commands.trigger(PlaySfx::Cue(SfxCue::ClockSpawn));
     let translation = positions.clock_spawn;
-    let clock_data = &clock_data.clocks[clock_count];
-
-    commands
-        .spawn((
-            Name::new("Clock"),
-            SpriteBundle {
-                texture: image_handles[&ImageKey::Clock].clone_weak(),
-                transform: Transform {
-                    translation: Vec3::new(translation.x, translation.y, 300.0),
-                    ..Default::default()
-                },
-                sprite: Sprite {
-                    anchor: Anchor::Custom(Vec2::new(-0.01, 0.0)),
-                    custom_size: Some(Vec2::new(128.0, 128.0)),
-                    ..default()
-                },
-                ..default()
+    commands.trigger(PlayEffect::Burst(
+        Burst::Dust,
+        benches.to_world(bench, (translation - Vec2::Y * CLOCK_RADIUS).extend(0.0)),
+    ));
+    commands.trigger(PlayEffect::Shake(0.25));
+    let rate = TickRate::default();
+    let synth = tick_synths.add(TickSynth::new(rate.clone(), clock_data.timbre));
+    let time_left = if drifting {
+        DRIFT_WIND_SECS
+    } else {
+        clock_data.time_left
+    };
+
+    let mut clock = commands.spawn((
+        Name::new("Clock"),
+        SpriteBundle {
+            texture: image_handles[&ImageKey::Clock].clone_weak(),
+            transform: Transform {
+                translation: Vec3::new(translation.x, translation.y, 300.0),
+                ..Default::default()
             },
-            StateScoped(Screen::Playing),
-            Clock {
-                is_main: false,
-                time_left: clock_data.time_left,
-                audio: clock_data.audio,
+            sprite: Sprite {
+                anchor: Anchor::Custom(Vec2::new(-0.01, 0.0)),
+                custom_size: Some(Vec2::new(128.0, 128.0)),
+                ..default()
             },
-            Interactable,
-        ))
+            ..default()
+        },
+        Clock {
+            is_main: false,
+            time_left,
+        },
+        Bench(bench),
+        ClockTicker::new(rate),
+        synth,
+        PlaybackSettings::LOOP,
+        Interactable,
+        Station(SPAWN_STATION),
+        Tween::default(),
+    ));
+    if drifting {
+        clock.insert(Drifting);
+    }
+    let clock = clock.id();
+    commands
+        .entity(clock)
+        .set_parent(benches.root(bench))
         .with_children(|parent| {
             parent.spawn((
                 SpriteBundle {
@@ -522,4 +708,12 @@ fn spawn_interact_clock(
                 ClockHandType::Minute,
             ));
         });
+    commands.trigger(Caption {
+        cue: if drifting {
+            CaptionCue::DriftingClock
+        } else {
+            CaptionCue::ClockSpawned
+        },
+        source: Some(clock),
+    });
 }
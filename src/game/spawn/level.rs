@@ -3,10 +3,16 @@
 use bevy::prelude::*;
 
 use crate::{
-    game::assets::{HandleMap, ImageKey},
+    game::{
+        assets::{HandleMap, ImageKey},
+        difficulty::{DifficultyChoice, RunRules},
+        run::{GameInput, RunRecorder},
+    },
     screen::{PlayingState, Screen},
+    AppSet,
 };
 
+use super::bench::Benches;
 use super::clock::{Positions, SpawnClock, SpawnMainClock};
 use super::player::SpawnPlayer;
 
@@ -14,38 +20,44 @@ pub(super) fn plugin(app: &mut App) {
     app.observe(spawn_level);
     app.observe(spawn_table);
     app.observe(spawn_oil);
-    app.observe(spawn_score);
     app.observe(spawn_clock_table);
     app.observe(spawn_oil_table);
     app.observe(spawn_background);
 
     app.insert_resource(Scoresource(0.0));
+    app.init_resource::<RunDuration>();
+    app.add_systems(
+        FixedUpdate,
+        tick_run_duration
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Playing))
+            .run_if(in_state(PlayingState::Playing)),
+    );
 }
 
 #[derive(Event, Debug)]
 pub struct SpawnLevel;
 
 #[derive(Event, Debug)]
-pub struct SpawnTable;
+pub struct SpawnTable(pub usize);
 
 #[derive(Event, Debug)]
-pub struct SpawnOil;
+pub struct SpawnOil(pub usize);
 
 #[derive(Event, Debug)]
-pub struct SpawnScore;
+pub struct SpawnClockTable(pub usize);
 
 #[derive(Event, Debug)]
-pub struct SpawnClockTable;
-
-#[derive(Event, Debug)]
-pub struct SpawnOilTable;
-
-#[derive(Component)]
-pub struct Score(pub f32);
+pub struct SpawnOilTable(pub usize);
 
+/// The points scored on every [`Bench`](super::bench::Bench) together.
 #[derive(Resource)]
 pub struct Scoresource(pub f32);
 
+/// How long the current run has lasted, in seconds.
+#[derive(Resource, Default)]
+pub struct RunDuration(pub f32);
+
 #[derive(Event, Debug)]
 pub struct SpawnBackground;
 
@@ -75,138 +87,140 @@ fn spawn_level(
     _trigger: Trigger<SpawnLevel>,
     mut commands: Commands,
     mut scoresource: ResMut<Scoresource>,
+    mut run_duration: ResMut<RunDuration>,
+    mut game_input: ResMut<GameInput>,
+    mut recorder: ResMut<RunRecorder>,
+    choice: Res<DifficultyChoice>,
+    mut rules: ResMut<RunRules>,
+    mut benches: ResMut<Benches>,
 ) {
+    // Before anything is spawned, so the player starts with the right leak.
+    *rules = choice.rules();
+    *benches = Benches::spawn(&mut commands, rules.mode);
     commands.trigger(SpawnBackground);
     commands.trigger(SpawnPlayer);
-    commands.trigger(SpawnTable);
-    commands.trigger(SpawnMainClock);
-    commands.trigger(SpawnClock);
-    commands.trigger(SpawnOil);
-    commands.trigger(SpawnScore);
-    commands.trigger(SpawnClockTable);
-    commands.trigger(SpawnOilTable);
+    for bench in 0..benches.count() {
+        commands.trigger(SpawnTable(bench));
+        commands.trigger(SpawnMainClock(bench));
+        commands.trigger(SpawnClock {
+            bench,
+            drifting: false,
+        });
+        commands.trigger(SpawnOil(bench));
+        commands.trigger(SpawnClockTable(bench));
+        commands.trigger(SpawnOilTable(bench));
+    }
     scoresource.0 = 0.0;
+    run_duration.0 = 0.0;
+    *game_input = GameInput::new(rules.mode.players());
+    recorder.start(rules.mode.players());
+}
+
+fn tick_run_duration(time: Res<Time>, mut run_duration: ResMut<RunDuration>) {
+    run_duration.0 += time.delta_seconds();
 }
 
 fn spawn_table(
-    _trigger: Trigger<SpawnTable>,
+    trigger: Trigger<SpawnTable>,
+    benches: Res<Benches>,
     mut commands: Commands,
     image_handles: Res<HandleMap<ImageKey>>,
 ) {
-    commands.spawn((
-        Name::new("Table"),
-        SpriteBundle {
-            texture: image_handles[&ImageKey::Table].clone_weak(),
-            transform: Transform {
-                translation: Vec3::new(0.0, -320.0, 10.0),
-                ..Default::default()
-            },
-            sprite: Sprite {
-                custom_size: Some(Vec2::new(768.0, 256.0)),
+    commands
+        .spawn((
+            Name::new("Table"),
+            SpriteBundle {
+                texture: image_handles[&ImageKey::Table].clone_weak(),
+                transform: Transform {
+                    translation: Vec3::new(0.0, -320.0, 10.0),
+                    ..Default::default()
+                },
+                sprite: Sprite {
+                    custom_size: Some(Vec2::new(768.0, 256.0)),
+                    ..default()
+                },
                 ..default()
             },
-            ..default()
-        },
-        StateScoped(Screen::Playing),
-    ));
+        ))
+        .set_parent(benches.root(trigger.event().0));
 }
 
 fn spawn_oil(
-    _trigger: Trigger<SpawnOil>,
+    trigger: Trigger<SpawnOil>,
+    benches: Res<Benches>,
     positions: Res<Positions>,
     mut commands: Commands,
     image_handles: Res<HandleMap<ImageKey>>,
 ) {
-    commands.spawn((
-        Name::new("Oil"),
-        SpriteBundle {
-            texture: image_handles[&ImageKey::OilCan].clone_weak(),
-            transform: Transform {
-                translation: positions.oil_can.extend(210.0),
+    commands
+        .spawn((
+            Name::new("Oil"),
+            SpriteBundle {
+                texture: image_handles[&ImageKey::OilCan].clone_weak(),
+                transform: Transform {
+                    translation: positions.oil_can.extend(210.0),
+                    ..default()
+                },
+                sprite: Sprite {
+                    custom_size: Some(Vec2::new(128.0, 128.0)),
+                    ..default()
+                },
                 ..default()
             },
-            sprite: Sprite {
-                custom_size: Some(Vec2::new(128.0, 128.0)),
-                ..default()
-            },
-            ..default()
-        },
-        StateScoped(Screen::Playing),
-    ));
-}
-
-fn spawn_score(_trigger: Trigger<SpawnScore>, mut commands: Commands) {
-    commands.spawn((
-        Name::new("Score"),
-        TextBundle {
-            text: Text {
-                sections: vec![TextSection {
-                    value: "0".to_string(),
-                    style: TextStyle {
-                        font_size: 50.0,
-                        color: Color::WHITE,
-                        ..default()
-                    },
-                }],
-                ..default()
-            },
-            transform: Transform {
-                translation: Vec3::new(-500.0, -300.0, 10.0),
-                ..default()
-            },
-            ..default()
-        },
-        Score(0.0),
-        StateScoped(Screen::Playing),
-    ));
+        ))
+        .set_parent(benches.root(trigger.event().0));
 }
 
 fn spawn_clock_table(
-    _trigger: Trigger<SpawnClockTable>,
+    trigger: Trigger<SpawnClockTable>,
+    benches: Res<Benches>,
     positions: Res<Positions>,
     mut commands: Commands,
     image_handles: Res<HandleMap<ImageKey>>,
 ) {
     let translation = Vec3::new(positions.clock_spawn.x, -340.0, 10.0);
-    commands.spawn((
-        Name::new("ClockTable"),
-        SpriteBundle {
-            texture: image_handles[&ImageKey::ClockTable].clone_weak(),
-            transform: Transform {
-                translation,
-                ..default()
-            },
-            sprite: Sprite {
-                custom_size: Some(Vec2::new(256.0, 256.0)),
+    commands
+        .spawn((
+            Name::new("ClockTable"),
+            SpriteBundle {
+                texture: image_handles[&ImageKey::ClockTable].clone_weak(),
+                transform: Transform {
+                    translation,
+                    ..default()
+                },
+                sprite: Sprite {
+                    custom_size: Some(Vec2::new(256.0, 256.0)),
+                    ..default()
+                },
                 ..default()
             },
-            ..default()
-        },
-        StateScoped(Screen::Playing),
-    ));
+        ))
+        .set_parent(benches.root(trigger.event().0));
 }
 
 fn spawn_oil_table(
-    _trigger: Trigger<SpawnOilTable>,
+    trigger: Trigger<SpawnOilTable>,
+    benches: Res<Benches>,
     positions: Res<Positions>,
     mut commands: Commands,
     image_handles: Res<HandleMap<ImageKey>>,
 ) {
     let translation = Vec3::new(positions.oil_can.x, -340.0, 10.0);
-    commands.spawn((
-        Name::new("OilTable"),
-        SpriteBundle {
-            texture: image_handles[&ImageKey::OilTable].clone_weak(),
-            transform: Transform {
-                translation,
+    commands
+        .spawn((
+            Name::new("OilTable"),
+            SpriteBundle {
+                texture: image_handles[&ImageKey::OilTable].clone_weak(),
+                transform: Transform {
+                    translation,
+                    ..default()
+                },
+                sprite: Sprite {
+                    custom_size: Some(Vec2::new(256.0, 256.0)),
+                    ..default()
+                },
                 ..default()
             },
-            sprite: Sprite {
-                custom_size: Some(Vec2::new(256.0, 256.0)),
-                ..default()
-            },
-            ..default()
-        },
-        StateScoped(Screen::Playing),
-    ));
+        ))
+        .set_parent(benches.root(trigger.event().0));
 }
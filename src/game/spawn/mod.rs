@@ -4,10 +4,11 @@
 
 use bevy::prelude::*;
 
+pub mod bench;
 pub mod clock;
 pub mod level;
 pub mod player;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins((level::plugin, player::plugin, clock::plugin));
+    app.add_plugins((bench::plugin, level::plugin, player::plugin, clock::plugin));
 }
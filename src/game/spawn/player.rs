@@ -2,134 +2,157 @@ use bevy::prelude::*;
 
 use crate::{
     game::{
+        animation::{PlayerAnimation, TOCKERY_CLIPS},
         assets::{HandleMap, ImageKey, SfxKey},
         audio::sfx::{PlayLoopingSfx, StopLoopingSfx},
+        difficulty::RunRules,
+        effects::{Burst, PlayEffect},
         movement::MovementController,
+        run::GameInput,
     },
+    leaderboard::replay::{drink, drinks, leak, MAX_OIL, PLAYER_STATIONS, STATIONS},
     screen::{PlayingState, Screen},
+    tween::Tween,
+    AppSet,
 };
 
-use super::clock::ClockController;
+use super::{
+    bench::{player_bench, Bench, Benches},
+    clock::{ClockController, Positions},
+};
 
 pub(super) fn plugin(app: &mut App) {
+    app.add_event::<OilDrunk>();
     app.observe(spawn_player);
     app.register_type::<Player>();
     app.add_systems(
-        Update,
-        oil_leak
-            .run_if(in_state(Screen::Playing))
-            .run_if(in_state(PlayingState::Playing)),
-    );
-    app.add_systems(
-        Update,
-        oil_drink
+        FixedUpdate,
+        (oil_drink, oil_leak)
+            .chain()
+            .in_set(AppSet::Update)
             .run_if(in_state(Screen::Playing))
             .run_if(in_state(PlayingState::Playing)),
     );
 }
 
+/// Below this much oil the player is running low.
+const LOW_OIL: f32 = 25.0;
+
 #[derive(Event, Debug)]
 pub struct SpawnPlayer;
 
+/// Which player this is, from 0. Only co-op and versus runs have a second one.
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
 #[reflect(Component)]
-pub struct Player;
+pub struct Player(pub usize);
+
+/// The second player shares Tockery's drawing, so they're told apart by colour.
+pub const PLAYER_NAMES: [&str; 2] = ["Tockery", "Tickery"];
+const PLAYER_TINTS: [Color; 2] = [Color::WHITE, Color::srgb(0.75, 0.9, 1.0)];
+const PLAYER_Y: f32 = -120.0;
 
 #[derive(Component)]
 pub struct OilMeter;
 
+/// Sent every tick the player drinks, with how much oil they got.
+#[derive(Event, Debug)]
+pub struct OilDrunk(pub f32);
+
 fn oil_drink(
     time: Res<Time>,
     mut commands: Commands,
-    mut control_query: Query<&mut ClockController>,
-    input: Res<ButtonInput<KeyCode>>,
+    mut control_query: Query<(&Player, &Bench, &mut ClockController)>,
+    input: Res<GameInput>,
+    positions: Res<Positions>,
+    benches: Res<Benches>,
+    mut ev_drunk: EventWriter<OilDrunk>,
 ) {
-    let mut controller = control_query.single_mut();
-    if controller.held_clock.is_some() {
-        return;
-    }
-
-    let drink = input.pressed(KeyCode::Space)
-        || input.pressed(KeyCode::KeyW)
-        || input.pressed(KeyCode::KeyS)
-        || input.pressed(KeyCode::ArrowUp)
-        || input.pressed(KeyCode::ArrowDown);
+    // The bench of whoever is drinking, if anyone is.
+    let mut drinking_at = None;
+    for (player, bench, mut controller) in &mut control_query {
+        let holding = controller.held_clock.is_some();
+        if !drinks(controller.index, holding, input.player(player.0)) {
+            continue;
+        }
+        drinking_at = Some(bench.0);
 
-    if controller.index != 6 || !drink {
-        commands.trigger(StopLoopingSfx::Key(SfxKey::OilDrink));
-        return;
+        let before = controller.oil_level;
+        controller.oil_level = drink(controller.oil_level, time.delta_seconds());
+        ev_drunk.send(OilDrunk(controller.oil_level - before));
     }
 
-    commands.trigger(PlayLoopingSfx::Key(SfxKey::OilDrink));
-
-    controller.oil_level += time.delta_seconds() * 10.0;
-    if controller.oil_level > 100.0 {
-        controller.oil_level = 100.0;
+    if let Some(bench) = drinking_at {
+        commands.trigger(PlayLoopingSfx::Key(SfxKey::OilDrink));
+        let spout = positions.oil_can - Vec2::Y * 50.0;
+        commands.trigger(PlayEffect::Burst(
+            Burst::OilDrip,
+            benches.to_world(bench, spout.extend(0.0)),
+        ));
+    } else {
+        commands.trigger(StopLoopingSfx::Key(SfxKey::OilDrink));
     }
 }
 
 fn oil_leak(
-    mut controller: Query<&mut ClockController, With<Player>>,
-    mut query: Query<(&mut Handle<Image>, &mut Sprite), With<OilMeter>>,
+    mut commands: Commands,
+    mut control_query: Query<(&Player, &mut ClockController)>,
+    mut meter_query: Query<
+        (&Parent, &mut Handle<Image>, &mut Sprite, &GlobalTransform),
+        With<OilMeter>,
+    >,
     time: Res<Time>,
+    rules: Res<RunRules>,
     images: Res<HandleMap<ImageKey>>,
     mut next_state: ResMut<NextState<PlayingState>>,
 ) {
-    let mut controller = controller.single_mut();
-    controller.oil_level -= time.delta_seconds() * controller.oil_leak;
-    if controller.oil_level <= 0.0 {
-        println!("Game over!");
-        next_state.set(PlayingState::GameOver);
-        return;
-    }
-    controller.oil_leak += time.delta_seconds() * 0.01;
-
-    let (mut image, mut sprite) = query.single_mut();
-    match controller.oil_level {
-        5.0..=15.0 => {
-            *image = images[&ImageKey::Oil10].clone_weak();
-            sprite.custom_size = Some(Vec2::new(64.0, 100.0));
+    // In player order, the same as the replay.
+    let mut controllers: Vec<_> = control_query.iter_mut().collect();
+    controllers.sort_by_key(|(player, _)| player.0);
+    for (_, controller) in &mut controllers {
+        let was_low = controller.oil_level <= LOW_OIL;
+        let controller = &mut **controller;
+        if !leak(
+            &mut controller.oil_level,
+            &mut controller.oil_leak,
+            &mut controller.leak_secs,
+            &rules,
+            time.delta_seconds(),
+        ) {
+            println!("Game over!");
+            next_state.set(PlayingState::GameOver);
+            return;
         }
-        15.0..=25.0 => {
-            *image = images[&ImageKey::Oil20].clone_weak();
-            sprite.custom_size = Some(Vec2::new(64.0, 100.0));
+        if controller.oil_level <= LOW_OIL && !was_low {
+            commands.trigger(PlayEffect::Shake(0.3));
         }
-        25.0..=35.0 => {
-            *image = images[&ImageKey::Oil30].clone_weak();
-            sprite.custom_size = Some(Vec2::new(64.0, 100.0));
-        }
-        35.0..=45.0 => {
-            *image = images[&ImageKey::Oil40].clone_weak();
-            sprite.custom_size = Some(Vec2::new(64.0, 100.0));
-        }
-        45.0..=55.0 => {
-            *image = images[&ImageKey::Oil50].clone_weak();
-            sprite.custom_size = Some(Vec2::new(64.0, 100.0));
-        }
-        55.0..=65.0 => {
-            *image = images[&ImageKey::Oil60].clone_weak();
-            sprite.custom_size = Some(Vec2::new(64.0, 100.0));
-        }
-        65.0..=75.0 => {
-            *image = images[&ImageKey::Oil70].clone_weak();
-            sprite.custom_size = Some(Vec2::new(64.0, 100.0));
-        }
-        75.0..=85.0 => {
-            *image = images[&ImageKey::Oil80].clone_weak();
-            sprite.custom_size = Some(Vec2::new(64.0, 100.0));
-        }
-        85.0..=95.0 => {
-            *image = images[&ImageKey::Oil90].clone_weak();
-            sprite.custom_size = Some(Vec2::new(64.0, 100.0));
-        }
-        95.0..=100.0 => {
-            *image = images[&ImageKey::OilFull].clone_weak();
-            sprite.custom_size = Some(Vec2::new(64.0, 100.0));
-        }
-        _ => {
-            // *image = images[&ImageKey::OilEmpty].clone_weak();
-            sprite.custom_size = Some(Vec2::new(1.0, 1.0));
+    }
+
+    for (parent, mut image, mut sprite, meter) in &mut meter_query {
+        let Ok((_, controller)) = control_query.get(parent.get()) else {
+            continue;
+        };
+        if controller.oil_level <= LOW_OIL {
+            commands.trigger(PlayEffect::Burst(Burst::OilDrip, meter.translation()));
         }
+        let key = match controller.oil_level {
+            5.0..=15.0 => ImageKey::Oil10,
+            15.0..=25.0 => ImageKey::Oil20,
+            25.0..=35.0 => ImageKey::Oil30,
+            35.0..=45.0 => ImageKey::Oil40,
+            45.0..=55.0 => ImageKey::Oil50,
+            55.0..=65.0 => ImageKey::Oil60,
+            65.0..=75.0 => ImageKey::Oil70,
+            75.0..=85.0 => ImageKey::Oil80,
+            85.0..=95.0 => ImageKey::Oil90,
+            95.0..=100.0 => ImageKey::OilFull,
+            _ => {
+                // *image = images[&ImageKey::OilEmpty].clone_weak();
+                sprite.custom_size = Some(Vec2::new(1.0, 1.0));
+                continue;
+            }
+        };
+        *image = images[&key].clone_weak();
+        sprite.custom_size = Some(Vec2::new(64.0, 100.0));
     }
 }
 
@@ -137,53 +160,66 @@ fn spawn_player(
     _trigger: Trigger<SpawnPlayer>,
     mut commands: Commands,
     image_handles: Res<HandleMap<ImageKey>>,
+    rules: Res<RunRules>,
+    benches: Res<Benches>,
 ) {
-    commands
-        .spawn((
-            Name::new("Player"),
-            Player,
-            SpriteBundle {
-                texture: image_handles[&ImageKey::Tockery].clone_weak(),
-                transform: Transform {
-                    scale: Vec3::splat(1.0),
-                    translation: Vec3::new(-330.0, -120.0, 0.0),
-                    ..default()
-                },
-                sprite: Sprite {
-                    custom_size: Some(Vec2::new(168.3, 383.35)),
-                    ..default()
-                },
-                ..default()
-            },
-            MovementController::default(),
-            ClockController {
-                index: 1,
-                oil_level: 100.0,
-                oil_leak: 1.4,
-                direction: Vec2::new(0.0, 0.0),
-                held_clock: None,
-                setting: false,
-                winding: false,
-                time_setting: 0.0,
-                time_winding: 0.0,
-            },
-            StateScoped(Screen::Playing),
-        ))
-        .with_children(|parent| {
-            parent.spawn((
+    for index in 0..rules.mode.players() {
+        let bench = player_bench(rules.mode, index);
+        // Players on a bench of their own start where the first player would.
+        let station = PLAYER_STATIONS[if benches.count() > 1 { 0 } else { index }];
+        let player_animation = PlayerAnimation::new(TOCKERY_CLIPS);
+        commands
+            .spawn((
+                Name::new(PLAYER_NAMES[index]),
+                Player(index),
+                Bench(bench),
                 SpriteBundle {
-                    texture: image_handles[&ImageKey::OilFull].clone_weak(),
+                    texture: image_handles[&ImageKey::Tockery].clone_weak(),
                     transform: Transform {
-                        translation: Vec3::new(5.0, 140.0, -1.0),
+                        scale: Vec3::splat(1.0),
+                        translation: Vec3::new(STATIONS[station].x, PLAYER_Y, index as f32),
                         ..default()
                     },
                     sprite: Sprite {
-                        custom_size: Some(Vec2::new(64.0, 100.0)),
+                        color: PLAYER_TINTS[index],
+                        custom_size: Some(Vec2::new(168.3, 383.35)),
                         ..default()
                     },
                     ..default()
                 },
-                OilMeter,
-            ));
-        });
+                player_animation,
+                MovementController::default(),
+                Tween::default(),
+                ClockController {
+                    index: station,
+                    oil_level: MAX_OIL,
+                    oil_leak: rules.leak_start,
+                    leak_secs: 0.0,
+                    direction: Vec2::new(0.0, 0.0),
+                    held_clock: None,
+                    setting: false,
+                    winding: false,
+                    time_setting: 0.0,
+                    time_winding: 0.0,
+                },
+            ))
+            .set_parent(benches.root(bench))
+            .with_children(|parent| {
+                parent.spawn((
+                    SpriteBundle {
+                        texture: image_handles[&ImageKey::OilFull].clone_weak(),
+                        transform: Transform {
+                            translation: Vec3::new(5.0, 140.0, -1.0),
+                            ..default()
+                        },
+                        sprite: Sprite {
+                            custom_size: Some(Vec2::new(64.0, 100.0)),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    OilMeter,
+                ));
+            });
+    }
 }
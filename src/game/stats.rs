@@ -0,0 +1,135 @@
+//! Statistics about the current run, collected from gameplay events.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    run::run_in_progress,
+    spawn::{
+        clock::{ClockRanDown, ClockScored, ClockWound, Interactable},
+        level::{RunDuration, SpawnLevel},
+        player::OilDrunk,
+    },
+};
+use crate::{
+    screen::{PlayingState, Screen},
+    AppSet,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<RunStats>();
+    app.observe(reset_stats);
+    app.add_systems(
+        FixedUpdate,
+        collect_stats
+            .after(AppSet::FixedUpdate)
+            .run_if(run_in_progress)
+            .run_if(in_state(Screen::Playing))
+            .run_if(in_state(PlayingState::Playing)),
+    );
+}
+
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct RunStats {
+    pub duration_secs: f32,
+    /// The most clocks that were out at once.
+    pub peak_clocks: usize,
+    /// Every clock of the run, in the order they appeared.
+    pub clocks: Vec<ClockStats>,
+    /// Seconds spent winding clocks.
+    pub wind_secs: f32,
+    pub oil_drunk: f32,
+    /// How often a clock was left to run down.
+    pub run_downs: u32,
+    /// The longest a single clock stayed in sync, in seconds.
+    pub best_sync_streak_secs: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct ClockStats {
+    #[serde(skip)]
+    entity: Option<Entity>,
+    /// Seconds since the clock appeared.
+    pub alive_secs: f32,
+    pub synced_secs: f32,
+    pub run_downs: u32,
+    #[serde(skip)]
+    streak_secs: f32,
+}
+
+impl ClockStats {
+    /// The share of its time the clock spent in sync, from 0 to 1.
+    pub fn synced_fraction(&self) -> f32 {
+        if self.alive_secs > 0.0 {
+            self.synced_secs / self.alive_secs
+        } else {
+            0.0
+        }
+    }
+}
+
+impl RunStats {
+    fn clock(&mut self, entity: Entity) -> &mut ClockStats {
+        let index = match self.clocks.iter().position(|c| c.entity == Some(entity)) {
+            Some(index) => index,
+            None => {
+                self.clocks.push(ClockStats {
+                    entity: Some(entity),
+                    ..default()
+                });
+                self.clocks.len() - 1
+            }
+        };
+        &mut self.clocks[index]
+    }
+}
+
+fn reset_stats(_trigger: Trigger<SpawnLevel>, mut stats: ResMut<RunStats>) {
+    *stats = default();
+}
+
+fn collect_stats(
+    time: Res<Time>,
+    run_duration: Res<RunDuration>,
+    mut stats: ResMut<RunStats>,
+    clock_query: Query<Entity, With<Interactable>>,
+    mut ev_scored: EventReader<ClockScored>,
+    mut ev_ran_down: EventReader<ClockRanDown>,
+    mut ev_wound: EventReader<ClockWound>,
+    mut ev_drunk: EventReader<OilDrunk>,
+) {
+    let dt = time.delta_seconds();
+    stats.duration_secs = run_duration.0;
+    stats.peak_clocks = stats.peak_clocks.max(clock_query.iter().count());
+    for entity in &clock_query {
+        stats.clock(entity).alive_secs += dt;
+    }
+
+    let mut synced = Vec::new();
+    for scored in ev_scored.read().filter(|scored| scored.synced) {
+        let clock = stats.clock(scored.clock);
+        clock.synced_secs += dt;
+        clock.streak_secs += dt;
+        synced.push(scored.clock);
+    }
+    for clock in &mut stats.clocks {
+        if !clock.entity.is_some_and(|entity| synced.contains(&entity)) {
+            clock.streak_secs = 0.0;
+        }
+    }
+    let best_streak = stats
+        .clocks
+        .iter()
+        .map(|c| c.streak_secs)
+        .fold(0.0, f32::max);
+    stats.best_sync_streak_secs = stats.best_sync_streak_secs.max(best_streak);
+
+    for ran_down in ev_ran_down.read() {
+        stats.clock(ran_down.0).run_downs += 1;
+        stats.run_downs += 1;
+    }
+    stats.wind_secs += ev_wound.read().count() as f32 * dt;
+    stats.oil_drunk += ev_drunk.read().map(|drunk| drunk.0).sum::<f32>();
+}
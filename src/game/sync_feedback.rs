@@ -0,0 +1,228 @@
+//! Shows which bench clocks count as in sync with the main clock.
+//! Running clocks get a glow that turns from red to green as their hands
+//! get closer to the main clock's, pulses while they are in sync,
+//! and every second in sync pops up how many points it is worth.
+//! With the colourblind option it goes from orange to blue instead.
+
+use bevy::{
+    color::Mix,
+    prelude::*,
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+};
+
+use super::{
+    accessibility::AccessibilitySettings,
+    difficulty::RunRules,
+    run::run_in_progress,
+    spawn::clock::{ClockScored, Interactable},
+};
+use crate::{
+    screen::{PlayingState, Screen},
+    AppSet,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        FixedUpdate,
+        record_sync
+            .after(AppSet::FixedUpdate)
+            .run_if(run_in_progress)
+            .run_if(in_state(Screen::Playing))
+            .run_if(in_state(PlayingState::Playing)),
+    );
+    app.add_systems(
+        Update,
+        (add_sync_glow, update_sync_glow, float_popups)
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+const GLOW_RADIUS: f32 = 76.0;
+/// Clocks this many sync tolerances off or more glow fully red.
+const GRADIENT_TOLERANCES: f32 = 8.0;
+const POPUP_SECS: f32 = 1.0;
+const POPUP_RISE: f32 = 60.0;
+
+const SYNCED_COLOR: Color = Color::srgb(0.45, 1.0, 0.45);
+/// Orange and blue, which stay apart for every kind of colourblindness.
+const COLOURBLIND_FAR_COLOR: Color = Color::srgb(0.9, 0.62, 0.0);
+const COLOURBLIND_SYNCED_COLOR: Color = Color::srgb(0.34, 0.71, 0.91);
+
+fn synced_color(settings: &AccessibilitySettings) -> Color {
+    if settings.colourblind {
+        COLOURBLIND_SYNCED_COLOR
+    } else {
+        SYNCED_COLOR
+    }
+}
+
+/// The colour of a clock that is `closeness` of the way from far off to in sync.
+fn offset_color(settings: &AccessibilitySettings, closeness: f32) -> Color {
+    if settings.colourblind {
+        Oklaba::from(COLOURBLIND_FAR_COLOR)
+            .mix(&COLOURBLIND_SYNCED_COLOR.into(), closeness)
+            .into()
+    } else {
+        Color::hsl(100.0 * closeness, 0.9, 0.5)
+    }
+}
+
+/// How a clock compared to the main clock on the last tick.
+#[derive(Component, Debug, Default)]
+struct SyncState {
+    running: bool,
+    synced: bool,
+    offset: f32,
+    synced_secs: f32,
+}
+
+#[derive(Component)]
+struct SyncGlow;
+
+#[derive(Component)]
+struct SyncPopup {
+    age: f32,
+}
+
+fn add_sync_glow(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    clock_query: Query<Entity, Added<Interactable>>,
+) {
+    for entity in &clock_query {
+        let glow = commands
+            .spawn((
+                Name::new("Sync Glow"),
+                MaterialMesh2dBundle {
+                    mesh: Mesh2dHandle(meshes.add(Circle::new(GLOW_RADIUS))),
+                    material: materials.add(Color::NONE),
+                    transform: Transform::from_xyz(0.0, 0.0, -1.0),
+                    ..default()
+                },
+                SyncGlow,
+            ))
+            .id();
+        commands
+            .entity(entity)
+            .insert(SyncState::default())
+            .add_child(glow);
+    }
+}
+
+fn record_sync(
+    mut commands: Commands,
+    time: Res<Time>,
+    rules: Res<RunRules>,
+    settings: Res<AccessibilitySettings>,
+    mut ev_scored: EventReader<ClockScored>,
+    mut clock_query: Query<(&mut SyncState, &GlobalTransform)>,
+) {
+    for (mut state, _) in &mut clock_query {
+        state.running = false;
+    }
+    for scored in ev_scored.read() {
+        let Ok((mut state, _)) = clock_query.get_mut(scored.clock) else {
+            continue;
+        };
+        state.running = true;
+        state.synced = scored.synced;
+        state.offset = scored.offset;
+    }
+
+    let dt = time.delta_seconds();
+    for (mut state, transform) in &mut clock_query {
+        if !state.running || !state.synced {
+            state.synced_secs = 0.0;
+            continue;
+        }
+        let before = state.synced_secs;
+        state.synced_secs += dt;
+        // On getting in sync, then once every second it stays that way.
+        if before == 0.0 || state.synced_secs.floor() > before.floor() {
+            let points = 2.0 * rules.score_multiplier;
+            let position = transform.translation() + Vec3::new(0.0, 80.0, 100.0);
+            commands.spawn((
+                Name::new("Sync Popup"),
+                Text2dBundle {
+                    text: Text::from_section(
+                        format!("+{points}/s"),
+                        TextStyle {
+                            font_size: 28.0,
+                            color: synced_color(&settings),
+                            ..default()
+                        },
+                    ),
+                    transform: Transform::from_translation(position),
+                    ..default()
+                },
+                SyncPopup { age: 0.0 },
+                StateScoped(Screen::Playing),
+            ));
+        }
+    }
+}
+
+fn update_sync_glow(
+    time: Res<Time>,
+    rules: Res<RunRules>,
+    settings: Res<AccessibilitySettings>,
+    clock_query: Query<(&SyncState, &Children)>,
+    mut glow_query: Query<(&Handle<ColorMaterial>, &mut Transform), With<SyncGlow>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for (state, children) in &clock_query {
+        for &child in children {
+            let Ok((material, mut transform)) = glow_query.get_mut(child) else {
+                continue;
+            };
+            let Some(material) = materials.get_mut(material) else {
+                continue;
+            };
+
+            if !state.running {
+                material.color = Color::NONE;
+                transform.scale = Vec3::ONE;
+                continue;
+            }
+            if state.synced {
+                let pulse = 0.5 + 0.5 * (time.elapsed_seconds() * 6.0).sin();
+                material.color = synced_color(&settings).with_alpha(0.5 + 0.3 * pulse);
+                // With reduced motion it pulses in brightness only.
+                let grow = if settings.reduced_motion {
+                    0.0
+                } else {
+                    0.05 * pulse
+                };
+                transform.scale = Vec3::splat(1.05 + grow);
+            } else {
+                let off = state.offset / (rules.sync_tolerance * GRADIENT_TOLERANCES);
+                let closeness = 1.0 - off.clamp(0.0, 1.0);
+                material.color =
+                    offset_color(&settings, closeness).with_alpha(0.2 + 0.2 * closeness);
+                transform.scale = Vec3::ONE;
+            }
+        }
+    }
+}
+
+fn float_popups(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut popup_query: Query<(Entity, &mut SyncPopup, &mut Transform, &mut Text)>,
+) {
+    let dt = time.delta_seconds();
+    for (entity, mut popup, mut transform, mut text) in &mut popup_query {
+        popup.age += dt;
+        if popup.age >= POPUP_SECS {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        transform.translation.y += dt * POPUP_RISE / POPUP_SECS;
+        let alpha = 1.0 - popup.age / POPUP_SECS;
+        for section in &mut text.sections {
+            section.style.color.set_alpha(alpha);
+        }
+    }
+}
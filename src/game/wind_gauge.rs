@@ -0,0 +1,196 @@
+//! A gauge under every bench clock showing how much winding it has left.
+//! The gauge flashes and a warning sounds every second in the last few seconds,
+//! and clocks that have run down are greyed out until they are wound again.
+
+use bevy::{prelude::*, sprite::Anchor};
+
+use super::{
+    accessibility::AccessibilitySettings,
+    audio::{bank::SfxCue, sfx::PlaySfx},
+    captions::{Caption, CaptionCue},
+    effects::{Burst, PlayEffect},
+    spawn::clock::{
+        Clock, ClockHandType, ClockRanDown, Drifting, Interactable, CLOCK_RADIUS, DRIFT_TINT,
+        FULL_WIND_SECS,
+    },
+};
+use crate::{
+    screen::{PlayingState, Screen},
+    AppSet,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            add_wind_gauge,
+            update_wind_gauge,
+            warn_running_down.run_if(in_state(PlayingState::Playing)),
+        )
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+/// Clocks warn this many seconds before running down.
+const WARNING_SECS: f32 = 5.0;
+const GAUGE_SIZE: Vec2 = Vec2::new(100.0, 10.0);
+const GAUGE_BORDER: f32 = 2.0;
+const GAUGE_Y: f32 = -76.0;
+
+const GAUGE_BACKGROUND: Color = Color::srgba(0.1, 0.08, 0.05, 0.8);
+const GAUGE_FULL: Color = Color::srgb(0.35, 0.85, 0.35);
+const GAUGE_LOW: Color = Color::srgb(0.95, 0.75, 0.2);
+const GAUGE_WARNING: Color = Color::srgb(0.95, 0.25, 0.2);
+const STOPPED_TINT: Color = Color::srgb(0.45, 0.45, 0.5);
+
+/// The second of the countdown the clock last warned at.
+#[derive(Component, Debug, Default)]
+struct RunDownWarning {
+    warned_at: Option<u32>,
+    last_time_left: f32,
+}
+
+#[derive(Component)]
+struct WindGaugeFill;
+
+fn add_wind_gauge(mut commands: Commands, clock_query: Query<Entity, Added<Interactable>>) {
+    for entity in &clock_query {
+        let gauge = commands
+            .spawn((
+                Name::new("Wind Gauge"),
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: GAUGE_BACKGROUND,
+                        custom_size: Some(GAUGE_SIZE + GAUGE_BORDER * 2.0),
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(0.0, GAUGE_Y, 5.0),
+                    ..default()
+                },
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: GAUGE_FULL,
+                            custom_size: Some(Vec2::new(0.0, GAUGE_SIZE.y)),
+                            anchor: Anchor::CenterLeft,
+                            ..default()
+                        },
+                        transform: Transform::from_xyz(-GAUGE_SIZE.x / 2.0, 0.0, 1.0),
+                        ..default()
+                    },
+                    WindGaugeFill,
+                ));
+            })
+            .id();
+        commands
+            .entity(entity)
+            .insert(RunDownWarning::default())
+            .add_child(gauge);
+    }
+}
+
+fn update_wind_gauge(
+    time: Res<Time>,
+    settings: Res<AccessibilitySettings>,
+    mut clock_query: Query<
+        (&Clock, &Children, &mut Sprite, Has<Drifting>),
+        (With<Interactable>, Without<WindGaugeFill>),
+    >,
+    mut hand_query: Query<
+        &mut Sprite,
+        (
+            With<ClockHandType>,
+            Without<Interactable>,
+            Without<WindGaugeFill>,
+        ),
+    >,
+    children_query: Query<&Children>,
+    mut fill_query: Query<&mut Sprite, With<WindGaugeFill>>,
+) {
+    let flash_on = (time.elapsed_seconds() * 8.0).sin() > 0.0;
+    for (clock, children, mut face, drifting) in &mut clock_query {
+        let running = clock.time_left > 0.0;
+        let fraction = (clock.time_left / FULL_WIND_SECS).clamp(0.0, 1.0);
+        let warning = running && clock.time_left <= WARNING_SECS;
+        let color = if warning {
+            GAUGE_WARNING.with_alpha(if flash_on { 1.0 } else { 0.3 })
+        } else if fraction < 0.5 {
+            GAUGE_LOW.mix(&GAUGE_FULL, fraction * 2.0)
+        } else {
+            GAUGE_FULL
+        };
+
+        let tint = if running { Color::WHITE } else { STOPPED_TINT };
+        face.color = settings.face_color(if running && drifting {
+            DRIFT_TINT
+        } else {
+            tint
+        });
+
+        for &child in children {
+            if let Ok(mut hand) = hand_query.get_mut(child) {
+                // High-contrast hands stay black, to still read on a stopped face.
+                hand.color = if running || settings.high_contrast {
+                    settings.hand_color()
+                } else {
+                    STOPPED_TINT
+                };
+                continue;
+            }
+            let Ok(gauge_children) = children_query.get(child) else {
+                continue;
+            };
+            for &fill in gauge_children {
+                if let Ok(mut sprite) = fill_query.get_mut(fill) {
+                    sprite.custom_size = Some(Vec2::new(GAUGE_SIZE.x * fraction, GAUGE_SIZE.y));
+                    sprite.color = color;
+                }
+            }
+        }
+    }
+}
+
+fn warn_running_down(
+    mut commands: Commands,
+    mut ev_ran_down: EventReader<ClockRanDown>,
+    mut clock_query: Query<(Entity, &Clock, &GlobalTransform, &mut RunDownWarning)>,
+) {
+    for (entity, clock, _, mut warning) in &mut clock_query {
+        // No warnings while the clock is being wound up from empty.
+        let winding = clock.time_left > warning.last_time_left;
+        warning.last_time_left = clock.time_left;
+        if clock.time_left <= 0.0 || clock.time_left > WARNING_SECS || winding {
+            warning.warned_at = None;
+            continue;
+        }
+        let second = clock.time_left.ceil() as u32;
+        if warning.warned_at != Some(second) {
+            warning.warned_at = Some(second);
+            commands.trigger(PlaySfx::Cue(SfxCue::ClockWarning));
+            commands.trigger(Caption {
+                cue: CaptionCue::RunningDown(second),
+                source: Some(entity),
+            });
+        }
+    }
+    let mut stopped = false;
+    for ClockRanDown(entity) in ev_ran_down.read() {
+        stopped = true;
+        if let Ok((_, _, transform, _)) = clock_query.get(*entity) {
+            let feet = transform.translation() - Vec3::Y * CLOCK_RADIUS;
+            commands.trigger(PlayEffect::Burst(Burst::Dust, feet));
+        }
+        commands.trigger(Caption {
+            cue: CaptionCue::ClockStopped,
+            source: Some(*entity),
+        });
+    }
+    if stopped {
+        commands.trigger(PlaySfx::Cue(SfxCue::ClockStopped));
+        commands.trigger(PlayEffect::Shake(0.4));
+        commands.trigger(PlayEffect::HitStop(0.08));
+    }
+}
@@ -0,0 +1,281 @@
+//! Places the leaderboard can be stored.
+
+use std::{fs, io, path::PathBuf};
+
+use bevy::prelude::*;
+use bevy_http_client::{
+    prelude::{HttpTypedRequestTrait, TypedResponse, TypedResponseError},
+    HttpClient, HttpResponse, HttpResponseError,
+};
+
+use super::{
+    LeaderboardBody, LeaderboardFailed, LeaderboardRecord, LeaderboardRequest, LeaderboardStatus,
+    ScoreFilter, ScoreSubmitted, ScoresFetched,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_request_type::<LeaderboardRecord>();
+    app.init_resource::<FetchesInFlight>();
+    app.add_systems(Update, forward_http_responses);
+}
+
+/// Somewhere scores can be fetched from and submitted to.
+/// Results are reported by sending [`ScoresFetched`] and [`ScoreSubmitted`] events,
+/// or [`LeaderboardFailed`] if something went wrong. This may happen several frames later.
+pub trait LeaderboardBackend: Send + Sync + 'static {
+    fn fetch_scores(&mut self, filter: ScoreFilter, commands: &mut Commands);
+
+    fn submit_score(&mut self, body: LeaderboardBody, commands: &mut Commands);
+}
+
+/// The leaderboard web API, see `aws/lambda/leaderboard`.
+pub struct HttpBackend {
+    url: String,
+}
+
+impl HttpBackend {
+    pub fn new(url: String) -> Self {
+        Self { url }
+    }
+}
+
+impl LeaderboardBackend for HttpBackend {
+    fn fetch_scores(&mut self, filter: ScoreFilter, commands: &mut Commands) {
+        let separator = if self.url.contains('?') { '&' } else { '?' };
+        let url = format!("{}{separator}{}", self.url, filter.to_query());
+        // Untyped, to get the URL back with the response and tell which filter it's for.
+        let request = HttpClient::new().get(&url).build();
+        commands.add(move |world: &mut World| {
+            world.resource_mut::<FetchesInFlight>().0 += 1;
+            world.send_event(request);
+        });
+    }
+
+    fn submit_score(&mut self, body: LeaderboardBody, commands: &mut Commands) {
+        let request = HttpClient::new()
+            .post(&self.url)
+            .json(&body)
+            .with_type::<LeaderboardRecord>();
+        commands.add(move |world: &mut World| {
+            world.send_event(request);
+        });
+    }
+}
+
+/// Fetches sent to the web API that haven't been answered yet,
+/// including ones that timed out and were sent again.
+#[derive(Resource, Default)]
+struct FetchesInFlight(usize);
+
+fn forward_http_responses(
+    status: Res<LeaderboardStatus>,
+    mut in_flight: ResMut<FetchesInFlight>,
+    mut fetched: EventReader<HttpResponse>,
+    mut fetch_errors: EventReader<HttpResponseError>,
+    mut submitted: EventReader<TypedResponse<LeaderboardRecord>>,
+    mut submit_errors: EventReader<TypedResponseError<LeaderboardRecord>>,
+    mut ev_fetched: EventWriter<ScoresFetched>,
+    mut ev_submitted: EventWriter<ScoreSubmitted>,
+    mut ev_failed: EventWriter<LeaderboardFailed>,
+) {
+    for res in fetched.read() {
+        in_flight.0 = in_flight.0.saturating_sub(1);
+        let filter = res
+            .url
+            .split_once('?')
+            .map_or_else(ScoreFilter::default, |(_, query)| {
+                ScoreFilter::from_query(query)
+            });
+        // The player has moved on to another category since this was requested.
+        if filter != status.fetch_filter() {
+            continue;
+        }
+        let records = if res.ok {
+            serde_json::from_slice::<Vec<LeaderboardRecord>>(&res.bytes).map_err(|e| e.to_string())
+        } else {
+            Err(format!(
+                "server returned {} {}",
+                res.status, res.status_text
+            ))
+        };
+        match records {
+            // Servers that don't know about categories send everything.
+            Ok(records) => {
+                ev_fetched.send(ScoresFetched {
+                    records: filter.apply(records),
+                    cached: false,
+                });
+            }
+            Err(error) => {
+                ev_failed.send(LeaderboardFailed {
+                    request: LeaderboardRequest::Fetch,
+                    error,
+                });
+            }
+        }
+    }
+    for err in fetch_errors.read() {
+        // Errors don't say which fetch they're for. While an abandoned one is still out
+        // this may be its error, so the current attempt is left to its own timeout.
+        let abandoned_in_flight = in_flight.0 > 1;
+        in_flight.0 = in_flight.0.saturating_sub(1);
+        if abandoned_in_flight {
+            continue;
+        }
+        ev_failed.send(LeaderboardFailed {
+            request: LeaderboardRequest::Fetch,
+            error: err.err.clone(),
+        });
+    }
+    for res in submitted.read() {
+        ev_submitted.send(ScoreSubmitted((**res).clone()));
+    }
+    for err in submit_errors.read() {
+        ev_failed.send(LeaderboardFailed {
+            request: LeaderboardRequest::Submit,
+            error: describe_http_error(err),
+        });
+    }
+}
+
+/// A server error response still arrives as a response, only failing to parse.
+/// Prefer its status over the parse error.
+fn describe_http_error<T>(error: &TypedResponseError<T>) -> String {
+    match &error.response {
+        Some(response) if !response.ok => {
+            format!(
+                "server returned {} {}",
+                response.status, response.status_text
+            )
+        }
+        _ => error.err.clone(),
+    }
+}
+
+/// A leaderboard that only lives as long as the game is running.
+#[derive(Default)]
+pub struct MemoryBackend {
+    records: Vec<LeaderboardRecord>,
+}
+
+impl LeaderboardBackend for MemoryBackend {
+    fn fetch_scores(&mut self, filter: ScoreFilter, commands: &mut Commands) {
+        send_fetched(filter.apply(sorted(self.records.clone())), commands);
+    }
+
+    fn submit_score(&mut self, body: LeaderboardBody, commands: &mut Commands) {
+        let record = new_record(&self.records, body);
+        self.records.push(record.clone());
+        send_submitted(record, commands);
+    }
+}
+
+/// A leaderboard stored in a local file.
+pub struct LocalFileBackend {
+    file: ScoreFile,
+}
+
+impl LocalFileBackend {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            file: ScoreFile::new(path),
+        }
+    }
+}
+
+impl LeaderboardBackend for LocalFileBackend {
+    fn fetch_scores(&mut self, filter: ScoreFilter, commands: &mut Commands) {
+        match self.file.scores() {
+            Ok(records) => send_fetched(filter.apply(records), commands),
+            Err(e) => send_failed(LeaderboardRequest::Fetch, e.to_string(), commands),
+        }
+    }
+
+    fn submit_score(&mut self, body: LeaderboardBody, commands: &mut Commands) {
+        match self.file.submit(body) {
+            Ok(record) => send_submitted(record, commands),
+            Err(e) => send_failed(LeaderboardRequest::Submit, e.to_string(), commands),
+        }
+    }
+}
+
+/// Scores stored as a JSON array in a file, in the same shape the web API returns.
+/// Also used by the `leaderboard_server` binary.
+pub struct ScoreFile {
+    path: PathBuf,
+}
+
+impl ScoreFile {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    /// All scores, sorted from best to worst.
+    pub fn scores(&self) -> io::Result<Vec<LeaderboardRecord>> {
+        self.load().map(sorted)
+    }
+
+    /// Store a new score, returning the record it was stored as.
+    pub fn submit(&self, body: LeaderboardBody) -> io::Result<LeaderboardRecord> {
+        let mut records = self.load()?;
+        let record = new_record(&records, body);
+        records.push(record.clone());
+        self.save(&records)?;
+        Ok(record)
+    }
+
+    fn load(&self) -> io::Result<Vec<LeaderboardRecord>> {
+        match fs::read(&self.path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).map_err(io::Error::other),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn save(&self, records: &[LeaderboardRecord]) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let bytes = serde_json::to_vec_pretty(records).map_err(io::Error::other)?;
+        fs::write(&self.path, bytes)
+    }
+}
+
+fn new_record(records: &[LeaderboardRecord], body: LeaderboardBody) -> LeaderboardRecord {
+    LeaderboardRecord {
+        id: format!("local-{}", records.len() + 1),
+        name: body.name,
+        score: body.score,
+        run: body.run,
+    }
+}
+
+fn sorted(mut records: Vec<LeaderboardRecord>) -> Vec<LeaderboardRecord> {
+    records.sort_by(|a, b| b.score.total_cmp(&a.score));
+    records
+}
+
+fn send_fetched(records: Vec<LeaderboardRecord>, commands: &mut Commands) {
+    commands.add(move |world: &mut World| {
+        world.send_event(ScoresFetched {
+            records,
+            cached: false,
+        });
+    });
+}
+
+fn send_submitted(record: LeaderboardRecord, commands: &mut Commands) {
+    commands.add(move |world: &mut World| {
+        world.send_event(ScoreSubmitted(record));
+    });
+}
+
+fn send_failed(request: LeaderboardRequest, error: String, commands: &mut Commands) {
+    commands.add(move |world: &mut World| {
+        world.send_event(LeaderboardFailed { request, error });
+    });
+}
@@ -0,0 +1,279 @@
+//! Which kind of run a score comes from, so easy and hard scores aren't mixed.
+
+use bevy::{prelude::*, utils::SystemTime};
+use serde::{Deserialize, Serialize};
+
+use super::LeaderboardRecord;
+
+#[derive(Serialize, Deserialize, Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum GameMode {
+    #[default]
+    Solo,
+    /// Two players at one keyboard, or one on a gamepad.
+    Coop,
+    /// Two players on a split bench, playing for the higher score.
+    /// Not ranked, since the replay can't check it.
+    Versus,
+}
+
+impl GameMode {
+    pub const ALL: [Self; 3] = [Self::Solo, Self::Coop, Self::Versus];
+    /// The modes that have a leaderboard.
+    pub const RANKED: [Self; 2] = [Self::Solo, Self::Coop];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Solo => "Solo",
+            Self::Coop => "Co-op",
+            Self::Versus => "Versus",
+        }
+    }
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Solo => "solo",
+            Self::Coop => "coop",
+            Self::Versus => "versus",
+        }
+    }
+
+    /// How many players a run in this mode has.
+    pub fn players(self) -> usize {
+        match self {
+            Self::Solo => 1,
+            Self::Coop | Self::Versus => 2,
+        }
+    }
+
+    pub fn ranked(self) -> bool {
+        Self::RANKED.contains(&self)
+    }
+}
+
+#[derive(Serialize, Deserialize, Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    /// Played with the player's own [`DifficultySettings`](super::DifficultySettings).
+    /// Not ranked, since the rules can make a run last forever.
+    Custom,
+}
+
+impl Difficulty {
+    pub const ALL: [Self; 4] = [Self::Easy, Self::Normal, Self::Hard, Self::Custom];
+    /// The difficulties that have a leaderboard.
+    pub const RANKED: [Self; 3] = [Self::Easy, Self::Normal, Self::Hard];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Easy => "Easy",
+            Self::Normal => "Normal",
+            Self::Hard => "Hard",
+            Self::Custom => "Custom",
+        }
+    }
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Easy => "easy",
+            Self::Normal => "normal",
+            Self::Hard => "hard",
+            Self::Custom => "custom",
+        }
+    }
+
+    pub fn ranked(self) -> bool {
+        Self::RANKED.contains(&self)
+    }
+}
+
+/// Details about the run a score was set in.
+/// Scores stored before these existed get the defaults.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct RunInfo {
+    pub mode: GameMode,
+    pub difficulty: Difficulty,
+    /// The game version the run was played on.
+    pub version: String,
+    pub duration_secs: f32,
+    /// When the score was set, in seconds since the Unix epoch.
+    pub date: u64,
+}
+
+impl RunInfo {
+    /// Whether the run can go on the leaderboard.
+    pub fn ranked(&self) -> bool {
+        self.mode.ranked() && self.difficulty.ranked()
+    }
+
+    /// A run that just ended.
+    pub fn finished(mode: GameMode, difficulty: Difficulty, duration_secs: f32) -> Self {
+        Self {
+            mode,
+            difficulty,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            duration_secs,
+            date: unix_now(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeWindow {
+    Daily,
+    Weekly,
+    #[default]
+    AllTime,
+}
+
+impl TimeWindow {
+    pub const ALL: [Self; 3] = [Self::Daily, Self::Weekly, Self::AllTime];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Daily => "Today",
+            Self::Weekly => "This week",
+            Self::AllTime => "All time",
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Self::Daily => "daily",
+            Self::Weekly => "weekly",
+            Self::AllTime => "alltime",
+        }
+    }
+
+    /// The oldest date that falls in this window.
+    fn since(self, now: u64) -> u64 {
+        match self {
+            Self::Daily => now.saturating_sub(24 * 60 * 60),
+            Self::Weekly => now.saturating_sub(7 * 24 * 60 * 60),
+            Self::AllTime => 0,
+        }
+    }
+}
+
+/// Which scores to fetch.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ScoreFilter {
+    pub mode: GameMode,
+    pub difficulty: Difficulty,
+    pub window: TimeWindow,
+}
+
+impl ScoreFilter {
+    /// All time scores in the category of a run played with `mode` and `difficulty`.
+    /// Unranked difficulties are shown the normal scores.
+    pub fn for_run(mode: GameMode, difficulty: Difficulty) -> Self {
+        Self {
+            mode,
+            difficulty: if difficulty.ranked() {
+                difficulty
+            } else {
+                Difficulty::Normal
+            },
+            window: TimeWindow::AllTime,
+        }
+    }
+
+    /// Keep only the records this filter selects.
+    pub fn apply(&self, records: Vec<LeaderboardRecord>) -> Vec<LeaderboardRecord> {
+        let since = self.window.since(unix_now());
+        records
+            .into_iter()
+            .filter(|record| {
+                record.run.mode == self.mode
+                    && record.run.difficulty == self.difficulty
+                    && record.run.date >= since
+            })
+            .collect()
+    }
+
+    /// The filter as URL query parameters.
+    pub fn to_query(self) -> String {
+        format!(
+            "mode={}&difficulty={}&window={}",
+            self.mode.key(),
+            self.difficulty.key(),
+            self.window.key()
+        )
+    }
+
+    /// Read a filter from URL query parameters. Anything missing or unknown
+    /// falls back to the default.
+    pub fn from_query(query: &str) -> Self {
+        let mut filter = Self::default();
+        for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+            match key {
+                "mode" => {
+                    if let Some(mode) = GameMode::RANKED.into_iter().find(|m| m.key() == value) {
+                        filter.mode = mode;
+                    }
+                }
+                "difficulty" => {
+                    if let Some(difficulty) =
+                        Difficulty::RANKED.into_iter().find(|d| d.key() == value)
+                    {
+                        filter.difficulty = difficulty;
+                    }
+                }
+                "window" => {
+                    if let Some(window) = TimeWindow::ALL.into_iter().find(|w| w.key() == value) {
+                        filter.window = window;
+                    }
+                }
+                _ => {}
+            }
+        }
+        filter
+    }
+}
+
+/// Seconds since the Unix epoch.
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_round_trip_through_queries() {
+        for mode in GameMode::RANKED {
+            for difficulty in Difficulty::RANKED {
+                for window in TimeWindow::ALL {
+                    let filter = ScoreFilter {
+                        mode,
+                        difficulty,
+                        window,
+                    };
+                    assert_eq!(ScoreFilter::from_query(&filter.to_query()), filter);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_query_values_fall_back_to_the_default() {
+        let filter = ScoreFilter::from_query("mode=versus&difficulty=custom&window=yearly&x=1");
+        assert_eq!(filter, ScoreFilter::default());
+    }
+
+    #[test]
+    fn unranked_runs_are_shown_normal_scores() {
+        let filter = ScoreFilter::for_run(GameMode::Coop, Difficulty::Custom);
+        assert_eq!(filter.difficulty, Difficulty::Normal);
+        assert_eq!(filter.mode, GameMode::Coop);
+    }
+}
@@ -0,0 +1,102 @@
+//! Choosing which leaderboard to talk to.
+//!
+//! The endpoint is read from, in order of priority:
+//! - the `--leaderboard <endpoint>` command line flag on native,
+//! - the `?leaderboard=<endpoint>` query parameter on web,
+//! - the `CLOCKERY_LEADERBOARD` environment variable at build time.
+//!
+//! An endpoint is either an `http(s)://` URL, `file:<path>` for a local JSON file,
+//! or `memory` for a leaderboard that only lives until the game closes.
+
+use std::{fmt, path::PathBuf};
+
+use bevy::prelude::*;
+
+use super::backend::{HttpBackend, LeaderboardBackend, LocalFileBackend, MemoryBackend};
+
+/// The production leaderboard.
+const PRODUCTION_URL: &str =
+    "https://sr5t5qmb4c.execute-api.us-east-1.amazonaws.com/prod/leaderboard";
+
+/// Dev builds shouldn't write to the production leaderboard unless asked to.
+const DEFAULT_ENDPOINT: &str = if cfg!(feature = "dev") {
+    "memory"
+} else {
+    PRODUCTION_URL
+};
+
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub enum LeaderboardConfig {
+    Http(String),
+    File(PathBuf),
+    Memory,
+}
+
+impl LeaderboardConfig {
+    /// Parse an endpoint string. Anything that isn't `memory` or a `file:` path
+    /// is treated as a URL.
+    pub fn parse(endpoint: &str) -> Self {
+        let endpoint = endpoint.trim();
+        if endpoint.eq_ignore_ascii_case("memory") {
+            Self::Memory
+        } else if let Some(path) = endpoint.strip_prefix("file:") {
+            Self::File(PathBuf::from(path.trim_start_matches("//")))
+        } else {
+            Self::Http(endpoint.to_string())
+        }
+    }
+
+    pub fn from_environment() -> Self {
+        let endpoint = runtime_endpoint()
+            .or_else(|| option_env!("CLOCKERY_LEADERBOARD").map(str::to_string))
+            .unwrap_or_else(|| DEFAULT_ENDPOINT.to_string());
+        Self::parse(&endpoint)
+    }
+
+    /// Create the backend this config points to.
+    pub fn backend(&self) -> Box<dyn LeaderboardBackend> {
+        match self {
+            Self::Http(url) => Box::new(HttpBackend::new(url.clone())),
+            Self::File(path) => Box::new(LocalFileBackend::new(path.clone())),
+            Self::Memory => Box::new(MemoryBackend::default()),
+        }
+    }
+}
+
+impl Default for LeaderboardConfig {
+    fn default() -> Self {
+        Self::parse(DEFAULT_ENDPOINT)
+    }
+}
+
+impl fmt::Display for LeaderboardConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Http(url) => write!(f, "{url}"),
+            Self::File(path) => write!(f, "file:{}", path.display()),
+            Self::Memory => write!(f, "memory"),
+        }
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn runtime_endpoint() -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--leaderboard" {
+            return args.next();
+        }
+        if let Some(endpoint) = arg.strip_prefix("--leaderboard=") {
+            return Some(endpoint.to_string());
+        }
+    }
+    None
+}
+
+#[cfg(target_family = "wasm")]
+fn runtime_endpoint() -> Option<String> {
+    let search = web_sys::window()?.location().search().ok()?;
+    web_sys::UrlSearchParams::new_with_str(&search)
+        .ok()?
+        .get("leaderboard")
+}
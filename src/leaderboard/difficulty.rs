@@ -0,0 +1,251 @@
+//! The rules each [`Difficulty`] is played with: how fast the oil leaks,
+//! how close clocks have to be to count as in sync, and when more clocks appear.
+//! Harder rules score more per second, so scores stay comparable.
+
+use serde::{Deserialize, Serialize};
+
+use super::{replay::TICK_SECS, Difficulty};
+
+/// How much faster the oil leaks as a run goes on.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum LeakCurve {
+    /// Leaks `per_sec` more every second.
+    Linear { per_sec: f32 },
+    /// Leaks `step` more every `every_secs` seconds.
+    Stepped { every_secs: f32, step: f32 },
+    /// Leaks `rate` times more every second, compounding.
+    Exponential { rate: f32 },
+}
+
+impl LeakCurve {
+    /// Oil lost per second once the oil has been leaking for `secs`.
+    pub fn leak(self, start: f32, secs: f32) -> f32 {
+        match self {
+            Self::Linear { per_sec } => start + per_sec * secs,
+            Self::Stepped { every_secs, step } => {
+                start + step * (secs / every_secs.max(TICK_SECS)).floor()
+            }
+            Self::Exponential { rate } => start * (rate * secs).exp(),
+        }
+    }
+
+    fn clamped(self) -> Self {
+        match self {
+            Self::Linear { per_sec } => Self::Linear {
+                per_sec: per_sec.clamp(0.0, 1.0),
+            },
+            Self::Stepped { every_secs, step } => Self::Stepped {
+                every_secs: every_secs.clamp(1.0, 600.0),
+                step: step.clamp(0.0, 5.0),
+            },
+            Self::Exponential { rate } => Self::Exponential {
+                rate: rate.clamp(0.0, 0.05),
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct DifficultySettings {
+    /// Oil lost per second at the start of a run.
+    pub leak_start: f32,
+    pub leak_curve: LeakCurve,
+    /// How close, in radians, both hands have to be to the main clock's to count as in sync.
+    pub sync_tolerance: f32,
+    /// The score needed for another clock, by how many clocks are out already.
+    pub spawn_thresholds: Vec<f32>,
+    /// Every point scored is worth this much.
+    pub score_multiplier: f32,
+}
+
+impl Default for DifficultySettings {
+    fn default() -> Self {
+        Self {
+            leak_start: 1.4,
+            leak_curve: LeakCurve::Linear { per_sec: 0.01 },
+            sync_tolerance: 0.1,
+            spawn_thresholds: vec![25.0, 100.0, 250.0, 500.0],
+            score_multiplier: 1.0,
+        }
+    }
+}
+
+/// There are only stations for this many clocks after the first.
+const MAX_THRESHOLDS: usize = 4;
+/// Custom runs are compared with normal ones over this many seconds.
+const FAIR_SECS: u32 = 120;
+
+impl Difficulty {
+    /// The rules of a preset, or `None` for [`Difficulty::Custom`].
+    pub fn preset(self) -> Option<DifficultySettings> {
+        match self {
+            Self::Easy => Some(DifficultySettings {
+                leak_start: 1.0,
+                leak_curve: LeakCurve::Linear { per_sec: 0.006 },
+                sync_tolerance: 0.15,
+                spawn_thresholds: vec![40.0, 150.0, 350.0, 700.0],
+                score_multiplier: 0.5,
+            }),
+            Self::Normal => Some(DifficultySettings::default()),
+            Self::Hard => Some(DifficultySettings {
+                leak_start: 1.8,
+                leak_curve: LeakCurve::Exponential { rate: 0.008 },
+                sync_tolerance: 0.07,
+                spawn_thresholds: vec![30.0, 110.0, 270.0, 540.0],
+                score_multiplier: 1.75,
+            }),
+            Self::Custom => None,
+        }
+    }
+}
+
+impl DifficultySettings {
+    /// The player's own rules, kept within bounds and with a score multiplier
+    /// based on how they compare to the normal ones.
+    pub fn custom(mut self) -> Self {
+        self.leak_start = self.leak_start.clamp(0.1, 10.0);
+        self.leak_curve = self.leak_curve.clamped();
+        self.sync_tolerance = self.sync_tolerance.clamp(0.01, 0.5);
+        self.spawn_thresholds.truncate(MAX_THRESHOLDS);
+        for threshold in &mut self.spawn_thresholds {
+            *threshold = threshold.max(1.0);
+        }
+        self.spawn_thresholds.sort_by(f32::total_cmp);
+        self.score_multiplier = self.fair_multiplier();
+        self
+    }
+
+    /// Roughly how much harder these rules are than the normal ones,
+    /// from the oil lost early in a run and how precise syncing has to be.
+    fn fair_multiplier(&self) -> f32 {
+        let normal = Self::default();
+        let leaked = |settings: &Self| -> f32 {
+            (0..FAIR_SECS).map(|secs| settings.leak(secs as f32)).sum()
+        };
+        let leak_ratio = leaked(self) / leaked(&normal);
+        let sync_ratio = (normal.sync_tolerance / self.sync_tolerance).sqrt();
+        let multiplier = (leak_ratio * sync_ratio).clamp(0.25, 3.0);
+        (multiplier * 100.0).round() / 100.0
+    }
+
+    /// Oil lost per second once the oil has been leaking for `secs`.
+    pub fn leak(&self, secs: f32) -> f32 {
+        self.leak_curve.leak(self.leak_start, secs)
+    }
+
+    /// The score needed for another clock while `clocks` are out,
+    /// or `None` if there's no room for more.
+    pub fn spawn_threshold(&self, clocks: usize) -> Option<f32> {
+        let index = clocks.checked_sub(1)?;
+        self.spawn_thresholds.get(index).copied()
+    }
+
+    /// How many bench clocks can be out at once.
+    pub fn max_clocks(&self) -> usize {
+        self.spawn_thresholds.len() + 1
+    }
+
+    /// Whether a run on `difficulty` may have been played with these rules.
+    pub fn allowed_for(&self, difficulty: Difficulty) -> bool {
+        match difficulty.preset() {
+            Some(preset) => *self == preset,
+            None => *self == self.clone().custom(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_rules_are_kept_within_bounds() {
+        let settings = DifficultySettings {
+            leak_start: 100.0,
+            leak_curve: LeakCurve::Stepped {
+                every_secs: 0.0,
+                step: 50.0,
+            },
+            sync_tolerance: 0.0,
+            spawn_thresholds: vec![500.0, -5.0, 30.0, 200.0, 900.0],
+            score_multiplier: 100.0,
+        }
+        .custom();
+        assert_eq!(settings.leak_start, 10.0);
+        assert_eq!(
+            settings.leak_curve,
+            LeakCurve::Stepped {
+                every_secs: 1.0,
+                step: 5.0
+            }
+        );
+        assert_eq!(settings.sync_tolerance, 0.01);
+        assert_eq!(settings.spawn_thresholds, vec![1.0, 30.0, 200.0, 500.0]);
+        assert_eq!(settings.score_multiplier, settings.fair_multiplier());
+    }
+
+    #[test]
+    fn fair_multiplier_follows_how_hard_the_rules_are() {
+        let normal = DifficultySettings::default();
+        assert_eq!(normal.fair_multiplier(), 1.0);
+
+        let leakier = DifficultySettings {
+            leak_start: normal.leak_start * 2.0,
+            ..normal.clone()
+        };
+        assert!(leakier.fair_multiplier() > 1.0);
+        let looser = DifficultySettings {
+            sync_tolerance: normal.sync_tolerance * 2.0,
+            ..normal.clone()
+        };
+        assert!(looser.fair_multiplier() < 1.0);
+
+        let hardest = DifficultySettings {
+            leak_start: 10.0,
+            sync_tolerance: 0.01,
+            ..normal.clone()
+        };
+        assert_eq!(hardest.fair_multiplier(), 3.0);
+        let easiest = DifficultySettings {
+            leak_start: 0.1,
+            leak_curve: LeakCurve::Linear { per_sec: 0.0 },
+            sync_tolerance: 0.5,
+            ..normal
+        };
+        assert_eq!(easiest.fair_multiplier(), 0.25);
+    }
+
+    #[test]
+    fn presets_are_only_allowed_for_their_difficulty() {
+        for difficulty in Difficulty::RANKED {
+            let preset = difficulty.preset().unwrap();
+            for other in Difficulty::RANKED {
+                assert_eq!(preset.allowed_for(other), difficulty == other);
+            }
+        }
+    }
+
+    #[test]
+    fn custom_runs_need_clamped_rules_and_a_fair_multiplier() {
+        let custom = DifficultySettings {
+            leak_start: 3.0,
+            ..DifficultySettings::default()
+        }
+        .custom();
+        assert!(custom.allowed_for(Difficulty::Custom));
+        assert!(!custom.allowed_for(Difficulty::Normal));
+
+        let boosted = DifficultySettings {
+            score_multiplier: custom.score_multiplier + 1.0,
+            ..custom.clone()
+        };
+        assert!(!boosted.allowed_for(Difficulty::Custom));
+        let unclamped = DifficultySettings {
+            leak_start: 0.0,
+            ..custom
+        };
+        assert!(!unclamped.allowed_for(Difficulty::Custom));
+    }
+}
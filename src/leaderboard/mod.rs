@@ -0,0 +1,144 @@
+//! Online leaderboard.
+//! Screens trigger [`FetchScores`] and [`SubmitScore`], and read the results
+//! from [`ScoresFetched`] and [`ScoreSubmitted`] events.
+//! Where the scores actually live is decided by the [`LeaderboardConfig`],
+//! and how the requests are going is tracked in [`LeaderboardStatus`].
+//! Scores that can't be submitted are queued until the leaderboard is back.
+//! Submissions carry a signed [`RunSummary`] that servers can check by replaying the run.
+
+mod backend;
+mod category;
+mod config;
+mod difficulty;
+mod name;
+mod offline;
+pub mod replay;
+mod request;
+pub(crate) mod storage;
+mod summary;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub use backend::{LeaderboardBackend, ScoreFile};
+pub use category::{unix_now, Difficulty, GameMode, RunInfo, ScoreFilter, TimeWindow};
+pub use config::LeaderboardConfig;
+pub use difficulty::{DifficultySettings, LeakCurve};
+pub use name::{NameError, NameRules};
+pub use offline::ScoreCache;
+pub use request::{LeaderboardStatus, OwnScores, RequestState, RetryLeaderboard};
+pub use summary::{run_key, RunSummary, VerifyError};
+
+pub(super) fn plugin(app: &mut App) {
+    let config = LeaderboardConfig::from_environment();
+    info!("Using leaderboard {config}");
+    app.insert_resource(Leaderboard(config.backend()));
+    app.insert_resource(config);
+    app.init_resource::<NameRules>();
+
+    app.add_event::<ScoresFetched>();
+    app.add_event::<ScoreSubmitted>();
+    app.add_event::<LeaderboardFailed>();
+    app.observe(fetch_scores);
+    app.observe(submit_score);
+
+    app.add_systems(Update, log_submissions);
+
+    app.add_plugins((backend::plugin, request::plugin, offline::plugin));
+}
+
+/// A single entry on the leaderboard, as returned by the server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LeaderboardRecord {
+    pub id: String,
+    pub name: String,
+    pub score: f32,
+    #[serde(flatten)]
+    pub run: RunInfo,
+}
+
+/// The body of a score submission.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LeaderboardBody {
+    pub name: String,
+    pub score: f32,
+    #[serde(flatten)]
+    pub run: RunInfo,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<RunSummary>,
+}
+
+impl LeaderboardBody {
+    /// Whether `record` is what this submission was stored as.
+    pub fn matches(&self, record: &LeaderboardRecord) -> bool {
+        self.name == record.name && self.score == record.score
+    }
+}
+
+/// The backend currently used to store scores.
+#[derive(Resource, Deref, DerefMut)]
+pub struct Leaderboard(pub Box<dyn LeaderboardBackend>);
+
+/// Trigger this event to request the scores matching a filter, sorted from best to worst.
+#[derive(Event, Debug)]
+pub struct FetchScores(pub ScoreFilter);
+
+/// Trigger this event to submit a new score.
+#[derive(Event, Debug)]
+pub struct SubmitScore(pub LeaderboardBody);
+
+/// Sent when the scores requested with [`FetchScores`] arrive.
+#[derive(Event, Debug, Clone)]
+pub struct ScoresFetched {
+    pub records: Vec<LeaderboardRecord>,
+    /// The leaderboard couldn't be reached, these are from the [`ScoreCache`].
+    pub cached: bool,
+}
+
+/// Sent when a score submitted with [`SubmitScore`] has been stored.
+#[derive(Event, Debug, Clone)]
+pub struct ScoreSubmitted(pub LeaderboardRecord);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaderboardRequest {
+    Fetch,
+    Submit,
+}
+
+/// Sent when a request couldn't be completed.
+#[derive(Event, Debug, Clone)]
+pub struct LeaderboardFailed {
+    pub request: LeaderboardRequest,
+    pub error: String,
+}
+
+fn fetch_scores(
+    trigger: Trigger<FetchScores>,
+    mut commands: Commands,
+    mut leaderboard: ResMut<Leaderboard>,
+    mut status: ResMut<LeaderboardStatus>,
+) {
+    let filter = trigger.event().0;
+    status.start_fetch(filter);
+    leaderboard.fetch_scores(filter, &mut commands);
+}
+
+fn submit_score(
+    trigger: Trigger<SubmitScore>,
+    mut commands: Commands,
+    mut leaderboard: ResMut<Leaderboard>,
+    mut status: ResMut<LeaderboardStatus>,
+) {
+    let body = trigger.event().0.clone();
+    status.start_submit(body.clone());
+    leaderboard.submit_score(body, &mut commands);
+}
+
+fn log_submissions(mut ev_submitted: EventReader<ScoreSubmitted>) {
+    for submitted in ev_submitted.read() {
+        info!(
+            "Submitted score {:.2} for {} ({})",
+            submitted.0.score, submitted.0.name, submitted.0.id
+        );
+    }
+}
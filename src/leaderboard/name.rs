@@ -0,0 +1,153 @@
+//! What players may call themselves on the leaderboard.
+//! Shared by the game, the `leaderboard_server` binary and the `leaderboard_check`
+//! function the web API stores scores through.
+
+use std::fmt;
+
+use bevy::prelude::*;
+
+/// The names nobody should see on the leaderboard.
+const DEFAULT_BLOCKLIST: &str = include_str!("name_blocklist.txt");
+/// Innocent words that happen to contain a blocked one.
+const DEFAULT_ALLOWLIST: &str = include_str!("name_allowlist.txt");
+
+/// Longest allowed name, in characters.
+pub const MAX_NAME_CHARS: usize = 16;
+
+#[derive(Resource, Debug, Clone)]
+pub struct NameRules {
+    pub max_chars: usize,
+    /// Normalized with [`normalize`].
+    blocklist: Vec<String>,
+    /// Normalized with [`normalize`].
+    allowlist: Vec<String>,
+}
+
+impl Default for NameRules {
+    fn default() -> Self {
+        Self::with_blocklist(DEFAULT_BLOCKLIST)
+    }
+}
+
+impl NameRules {
+    /// Rules refusing names containing any line of `blocklist`,
+    /// unless it's part of a word on the default allowlist.
+    pub fn with_blocklist(blocklist: &str) -> Self {
+        Self {
+            max_chars: MAX_NAME_CHARS,
+            blocklist: parse_list(blocklist),
+            allowlist: parse_list(DEFAULT_ALLOWLIST),
+        }
+    }
+
+    /// Whether `c` can appear in a name. Limited to what the UI font can draw.
+    pub fn allows_char(c: char) -> bool {
+        c.is_ascii_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.' | '!' | '?' | '\'')
+    }
+
+    /// Check `name`, returning it as it should be submitted.
+    pub fn validate(&self, name: &str) -> Result<String, NameError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(NameError::Empty);
+        }
+        if name.chars().count() > self.max_chars {
+            return Err(NameError::TooLong(self.max_chars));
+        }
+        if let Some(c) = name.chars().find(|&c| !Self::allows_char(c)) {
+            return Err(NameError::InvalidChar(c));
+        }
+        // Allowed words are cut out first, so "Dickens" is fine but "Dickensfuck" isn't.
+        let mut normalized = normalize(name);
+        for allowed in &self.allowlist {
+            normalized = normalized.replace(allowed.as_str(), " ");
+        }
+        if self
+            .blocklist
+            .iter()
+            .any(|blocked| normalized.contains(blocked.as_str()))
+        {
+            return Err(NameError::Blocked);
+        }
+        Ok(name.to_string())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NameError {
+    Empty,
+    TooLong(usize),
+    InvalidChar(char),
+    Blocked,
+}
+
+impl fmt::Display for NameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Type your name"),
+            Self::TooLong(max) => write!(f, "Names can be at most {max} characters"),
+            Self::InvalidChar(c) => write!(f, "Names can't contain '{c}'"),
+            Self::Blocked => write!(f, "Please pick another name"),
+        }
+    }
+}
+
+/// The entries of a name list. Empty lines and lines starting with `#` are ignored.
+fn parse_list(list: &str) -> Vec<String> {
+    list.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(normalize)
+        .collect()
+}
+
+/// Lowercase letters and digits only, with common substitutions undone,
+/// so that "B.4.D" matches "bad".
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter_map(|c| match c.to_ascii_lowercase() {
+            '0' => Some('o'),
+            '1' | '!' => Some('i'),
+            '3' => Some('e'),
+            '4' | '@' => Some('a'),
+            '5' | '$' => Some('s'),
+            '7' => Some('t'),
+            c if c.is_ascii_alphanumeric() => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocks_names_containing_a_blocked_word() {
+        let rules = NameRules::default();
+        for name in ["fuckface", "shithead", "xxfuckxx", "F.U.C.K", "sh1t"] {
+            assert_eq!(rules.validate(name), Err(NameError::Blocked), "{name}");
+        }
+    }
+
+    #[test]
+    fn allows_words_on_the_allowlist() {
+        let rules = NameRules::default();
+        for name in ["Dickens", "Grape Ape", "Scunthorpe"] {
+            assert_eq!(rules.validate(name), Ok(name.to_string()), "{name}");
+        }
+        assert_eq!(rules.validate("Dickensfuck"), Err(NameError::Blocked));
+    }
+
+    #[test]
+    fn checks_length_and_characters() {
+        let rules = NameRules::default();
+        assert_eq!(rules.validate("  "), Err(NameError::Empty));
+        assert_eq!(
+            rules.validate(&"a".repeat(MAX_NAME_CHARS + 1)),
+            Err(NameError::TooLong(MAX_NAME_CHARS))
+        );
+        assert_eq!(rules.validate("a<b"), Err(NameError::InvalidChar('<')));
+        assert_eq!(rules.validate(" Tockery "), Ok("Tockery".to_string()));
+    }
+}
@@ -0,0 +1,172 @@
+//! Keeps the leaderboard useful without a connection.
+//! Submissions that couldn't be sent are queued and sent again once
+//! the leaderboard answers, and the last fetched scores are shown
+//! when fetching fails.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    storage, unix_now, Leaderboard, LeaderboardBody, LeaderboardFailed, LeaderboardRecord,
+    LeaderboardRequest, LeaderboardStatus, RequestState, ScoreFilter, ScoreSubmitted,
+    ScoresFetched,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(OfflineQueue::load());
+    app.insert_resource(ScoreCache::load());
+    app.add_systems(
+        Update,
+        (
+            handle_failures.run_if(resource_changed::<LeaderboardStatus>),
+            flush_queue,
+        )
+            .chain(),
+    );
+}
+
+const QUEUE_KEY: &str = "score_queue";
+const CACHE_KEY: &str = "score_cache";
+
+/// Scores waiting to be submitted.
+#[derive(Resource, Debug, Default)]
+pub struct OfflineQueue {
+    scores: Vec<LeaderboardBody>,
+    /// The queued score that is being sent right now.
+    in_flight: Option<LeaderboardBody>,
+}
+
+impl OfflineQueue {
+    fn load() -> Self {
+        Self {
+            scores: storage::load(QUEUE_KEY).unwrap_or_default(),
+            in_flight: None,
+        }
+    }
+
+    fn push(&mut self, body: LeaderboardBody) {
+        if !self.scores.contains(&body) {
+            self.scores.push(body);
+            storage::save(QUEUE_KEY, &self.scores);
+        }
+    }
+
+    fn remove(&mut self, record: &LeaderboardRecord) {
+        let Some(index) = self.scores.iter().position(|body| body.matches(record)) else {
+            return;
+        };
+        let body = self.scores.remove(index);
+        if self.in_flight.as_ref() == Some(&body) {
+            self.in_flight = None;
+        }
+        storage::save(QUEUE_KEY, &self.scores);
+    }
+}
+
+/// The last scores that were fetched successfully.
+#[derive(Resource, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct ScoreCache {
+    records: Vec<LeaderboardRecord>,
+    filter: ScoreFilter,
+    /// Seconds since the Unix epoch.
+    updated_at: u64,
+}
+
+impl ScoreCache {
+    fn load() -> Self {
+        storage::load(CACHE_KEY).unwrap_or_default()
+    }
+
+    fn update(&mut self, records: Vec<LeaderboardRecord>, filter: ScoreFilter) {
+        self.records = records;
+        self.filter = filter;
+        self.updated_at = unix_now();
+        storage::save(CACHE_KEY, self);
+    }
+
+    /// When the cached scores were fetched, for the player.
+    pub fn last_updated(&self) -> Option<String> {
+        if self.updated_at == 0 {
+            return None;
+        }
+        let minutes = unix_now().saturating_sub(self.updated_at) / 60;
+        Some(match minutes {
+            0 => "Last updated just now".to_string(),
+            1..=59 => format!("Last updated {minutes} min ago"),
+            60..=2879 => format!("Last updated {} h ago", minutes / 60),
+            _ => format!("Last updated {} days ago", minutes / 1440),
+        })
+    }
+}
+
+/// Queue submissions that ran out of attempts,
+/// and fall back to the cache when fetching does.
+fn handle_failures(
+    mut commands: Commands,
+    status: Res<LeaderboardStatus>,
+    mut queue: ResMut<OfflineQueue>,
+    cache: Res<ScoreCache>,
+    mut fetch_failed: Local<bool>,
+) {
+    if let (RequestState::Failed(_), Some(body)) = (&status.submit, &status.pending_submit) {
+        queue.push(body.clone());
+    }
+
+    let failed = status.fetch.is_failed();
+    let cached = cache.updated_at != 0 && cache.filter == status.fetch_filter();
+    if failed && !*fetch_failed && cached {
+        let event = ScoresFetched {
+            records: cache.records.clone(),
+            cached: true,
+        };
+        commands.add(move |world: &mut World| {
+            world.send_event(event);
+        });
+    }
+    *fetch_failed = failed;
+}
+
+/// Once the leaderboard answers, send the queued scores one at a time.
+fn flush_queue(
+    mut commands: Commands,
+    status: Res<LeaderboardStatus>,
+    mut leaderboard: ResMut<Leaderboard>,
+    mut queue: ResMut<OfflineQueue>,
+    mut cache: ResMut<ScoreCache>,
+    mut ev_fetched: EventReader<ScoresFetched>,
+    mut ev_submitted: EventReader<ScoreSubmitted>,
+    mut ev_failed: EventReader<LeaderboardFailed>,
+) {
+    let mut online = false;
+    for fetched in ev_fetched.read().filter(|fetched| !fetched.cached) {
+        cache.update(fetched.records.clone(), status.fetch_filter());
+        online = true;
+    }
+    for submitted in ev_submitted.read() {
+        queue.remove(&submitted.0);
+        online = true;
+    }
+    if ev_failed
+        .read()
+        .any(|failed| failed.request == LeaderboardRequest::Submit)
+    {
+        // Try again after the next successful request.
+        queue.in_flight = None;
+        return;
+    }
+
+    // Don't get in the way of a submission the player is waiting on.
+    let busy = matches!(
+        status.submit,
+        RequestState::Loading { .. } | RequestState::Waiting { .. }
+    );
+    if !online || busy || queue.in_flight.is_some() {
+        return;
+    }
+    if let Some(body) = queue.scores.first().cloned() {
+        info!("Sending queued score {:.2} for {}", body.score, body.name);
+        queue.in_flight = Some(body.clone());
+        leaderboard.submit_score(body, &mut commands);
+    }
+}
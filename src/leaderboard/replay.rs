@@ -0,0 +1,637 @@
+//! The rules of the game, and a headless run of them used to check a run by playing its inputs back.
+//! The game systems in `game::spawn` and `game::movement` call the same rule functions
+//! as the [`Replay`], so the two can't disagree. What changes with the difficulty
+//! is in [`DifficultySettings`].
+
+use std::ops::AddAssign;
+
+use bevy::math::{Quat, Vec2};
+use hmac_sha256::Hash;
+use serde::{Deserialize, Serialize};
+
+use super::DifficultySettings;
+
+/// The game logic runs at a fixed rate so that runs can be played back exactly.
+pub const TICK_HZ: f64 = 64.0;
+pub const TICK_SECS: f32 = 1.0 / TICK_HZ as f32;
+/// The longest run that will be played back, six hours.
+pub const MAX_RUN_TICKS: u32 = 6 * 60 * 60 * TICK_HZ as u32;
+
+/// Where the player can stand, from the clock spawn on the left to the oil can on the right.
+pub const STATIONS: [Vec2; 7] = [
+    Vec2::new(-550.0, -185.0),
+    Vec2::new(-330.0, -220.0),
+    Vec2::new(-180.0, -220.0),
+    Vec2::new(-30.0, -220.0),
+    Vec2::new(120.0, -220.0),
+    Vec2::new(270.0, -220.0),
+    Vec2::new(550.0, -200.0),
+];
+pub const SPAWN_STATION: usize = 0;
+pub const OIL_STATION: usize = 6;
+/// Where each player starts, in player order. Co-op runs have two players.
+pub const PLAYER_STATIONS: [usize; 2] = [1, 5];
+
+/// Radians per second the hands of a running clock turn.
+pub const HOUR_HAND_SPEED: f32 = -0.008726646 * 2.0;
+pub const MINUTE_HAND_SPEED: f32 = -0.1047198 * 2.0;
+/// Radians per second the hands turn while setting, per second the button has been held.
+pub const HOUR_SET_SPEED: f32 = -0.008726646 * 100.0;
+pub const MINUTE_SET_SPEED: f32 = -0.1047198 * 100.0;
+/// Seconds a clock gains per second of winding.
+pub const WIND_RATE: f32 = 6.0;
+/// Setting and winding stop speeding up after being held this long.
+pub const MAX_HOLD_SECS: f32 = 3.0;
+
+pub const MAX_OIL: f32 = 100.0;
+/// Oil gained per second of drinking.
+pub const OIL_DRINK_RATE: f32 = 10.0;
+
+/// The buttons that drive the game during one tick, as bit sets.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TickInput {
+    /// Buttons held down.
+    pub held: u8,
+    /// Buttons pressed since the previous tick.
+    pub pressed: u8,
+}
+
+impl TickInput {
+    pub const LEFT: u8 = 1 << 0;
+    pub const RIGHT: u8 = 1 << 1;
+    pub const WIND: u8 = 1 << 2;
+    pub const SET: u8 = 1 << 3;
+    pub const ACTION: u8 = 1 << 4;
+
+    pub fn held(self, buttons: u8) -> bool {
+        self.held & buttons != 0
+    }
+
+    pub fn pressed(self, buttons: u8) -> bool {
+        self.pressed & buttons != 0
+    }
+}
+
+/// Every tick's input of a run, run-length encoded as `[ticks, held, pressed]`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct InputLog(Vec<[u32; 3]>);
+
+impl InputLog {
+    pub fn push(&mut self, input: TickInput) {
+        let entry = [1, input.held as u32, input.pressed as u32];
+        match self.0.last_mut() {
+            Some(last) if last[1..] == entry[1..] => last[0] += 1,
+            _ => self.0.push(entry),
+        }
+    }
+
+    /// How many ticks the log covers, `None` if that doesn't fit in a `u32`.
+    pub fn ticks(&self) -> Option<u32> {
+        self.0
+            .iter()
+            .try_fold(0u32, |total, &[ticks, ..]| total.checked_add(ticks))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = TickInput> + '_ {
+        self.0.iter().flat_map(|&[ticks, held, pressed]| {
+            let input = TickInput {
+                held: held as u8,
+                pressed: pressed as u8,
+            };
+            std::iter::repeat_n(input, ticks as usize)
+        })
+    }
+
+    /// SHA-256 of the log, as hex.
+    pub fn hash(&self) -> String {
+        let mut hash = Hash::new();
+        for entry in &self.0 {
+            for value in entry {
+                hash.update(value.to_le_bytes());
+            }
+        }
+        to_hex(&hash.finalize())
+    }
+}
+
+/// Which way `input` walks, -1 for left, 1 for right or 0.
+pub fn walk_direction(input: TickInput) -> f32 {
+    let mut direction = 0.0;
+    if input.pressed(TickInput::LEFT) {
+        direction -= 1.0;
+    }
+    if input.pressed(TickInput::RIGHT) {
+        direction += 1.0;
+    }
+    direction
+}
+
+/// The station a player walks to. There is nothing left of the clock spawn,
+/// or right of the oil can.
+pub fn walk(station: usize, direction: f32) -> usize {
+    if station == SPAWN_STATION && direction < 0.0 {
+        return station;
+    }
+    ((station as i32 + direction as i32) as usize).min(OIL_STATION)
+}
+
+/// Whether a held clock can be put down at `station`, where there are `clocks` including it.
+pub fn can_put_down(station: usize, clocks: usize) -> bool {
+    station != SPAWN_STATION && clocks == 1
+}
+
+/// Keep track of how long a button has been held, starting from 0 on the tick it goes down.
+pub fn track_hold(holding: &mut bool, held_secs: &mut f32, down: bool, dt: f32) {
+    if !down {
+        *holding = false;
+        *held_secs = 0.0;
+    } else if *holding {
+        *held_secs += dt;
+    } else {
+        *holding = true;
+        *held_secs = 0.0;
+    }
+}
+
+/// How long a button counts as held on the held clock, which stops at [`MAX_HOLD_SECS`].
+pub fn clock_hold_secs(held_secs: f32, dt: f32) -> f32 {
+    (held_secs + dt).min(MAX_HOLD_SECS)
+}
+
+/// How long a clock runs for after a tick of winding.
+pub fn wind(time_left: f32, dt: f32) -> f32 {
+    time_left + dt * WIND_RATE
+}
+
+/// How far the hour and minute hands turn in a tick of setting, after holding set for `held_secs`.
+pub fn set_turn(held_secs: f32, dt: f32) -> (f32, f32) {
+    (
+        dt * HOUR_SET_SPEED * held_secs,
+        dt * MINUTE_SET_SPEED * held_secs,
+    )
+}
+
+/// How far the hour and minute hands of a running clock turn in a tick, `rate` times as fast as normal.
+pub fn run_turn(rate: f32, dt: f32) -> (f32, f32) {
+    (dt * HOUR_HAND_SPEED * rate, dt * MINUTE_HAND_SPEED * rate)
+}
+
+/// How long a clock runs for after a tick of running down.
+pub fn run_down(time_left: f32, dt: f32) -> f32 {
+    (time_left - dt).max(0.0)
+}
+
+/// Whether a player at `station`, holding a clock or not, drinks from the oil can.
+pub fn drinks(station: usize, holding: bool, input: TickInput) -> bool {
+    !holding
+        && station == OIL_STATION
+        && input.held(TickInput::ACTION | TickInput::WIND | TickInput::SET)
+}
+
+/// The oil level after a tick of drinking.
+pub fn drink(oil_level: f32, dt: f32) -> f32 {
+    (oil_level + dt * OIL_DRINK_RATE).min(MAX_OIL)
+}
+
+/// Leak a tick of oil, and leak faster the longer it has been leaking.
+/// Returns `false` once the oil has run out, which ends the run.
+pub fn leak(
+    oil_level: &mut f32,
+    oil_leak: &mut f32,
+    leak_secs: &mut f32,
+    settings: &DifficultySettings,
+    dt: f32,
+) -> bool {
+    *oil_level -= dt * *oil_leak;
+    if *oil_level <= 0.0 {
+        return false;
+    }
+    *leak_secs += dt;
+    *oil_leak = settings.leak(*leak_secs);
+    true
+}
+
+/// Whether a clock whose hands are this far from the main clock's, in radians, is in sync.
+pub fn in_sync(hour_diff: f32, minute_diff: f32, settings: &DifficultySettings) -> bool {
+    hour_diff < settings.sync_tolerance && minute_diff < settings.sync_tolerance
+}
+
+/// What a running clock scores in a tick, with `score` points so far:
+/// `points` for running, and as many again in sync with the main clock.
+/// Out of sync, a drifting clock takes `points` away instead, down to 0.
+pub fn score_clock(score: f32, points: f32, synced: bool, drifting: bool) -> ScoreBreakdown {
+    ScoreBreakdown {
+        running: if drifting && !synced {
+            -points.min(score)
+        } else {
+            points
+        },
+        synced: if synced { points } else { 0.0 },
+    }
+}
+
+pub(super) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Where the points of a score came from.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct ScoreBreakdown {
+    /// Points for keeping clocks running.
+    pub running: f32,
+    /// Bonus points for running clocks in sync with the main clock.
+    pub synced: f32,
+}
+
+impl ScoreBreakdown {
+    /// Add these points to `score`, the running points first.
+    pub fn add_to(self, score: &mut f32) {
+        *score += self.running;
+        *score += self.synced;
+    }
+}
+
+impl AddAssign for ScoreBreakdown {
+    fn add_assign(&mut self, points: Self) {
+        self.running += points.running;
+        self.synced += points.synced;
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ReplayClock {
+    x: f32,
+    hour: Quat,
+    minute: Quat,
+    time_left: f32,
+}
+
+impl ReplayClock {
+    fn spawn() -> Self {
+        Self {
+            x: STATIONS[SPAWN_STATION].x,
+            hour: Quat::IDENTITY,
+            minute: Quat::IDENTITY,
+            time_left: 0.0,
+        }
+    }
+}
+
+/// One player's part of a [`Replay`].
+#[derive(Debug, Clone)]
+struct ReplayPlayer {
+    station: usize,
+    held_clock: Option<usize>,
+    setting: bool,
+    time_setting: f32,
+    winding: bool,
+    time_winding: f32,
+    oil_level: f32,
+    oil_leak: f32,
+    leak_secs: f32,
+}
+
+impl ReplayPlayer {
+    fn new(station: usize, settings: &DifficultySettings) -> Self {
+        Self {
+            station,
+            held_clock: None,
+            setting: false,
+            time_setting: 0.0,
+            winding: false,
+            time_winding: 0.0,
+            oil_level: MAX_OIL,
+            oil_leak: settings.leak_start,
+            leak_secs: 0.0,
+        }
+    }
+}
+
+/// The state of a run being played back, one tick at a time.
+/// Players act in order, so the first one gets a clock both reach for.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    players: Vec<ReplayPlayer>,
+    main_hour: Quat,
+    main_minute: Quat,
+    clocks: Vec<ReplayClock>,
+    settings: DifficultySettings,
+    pub score: f32,
+    pub breakdown: ScoreBreakdown,
+    pub ticks: u32,
+    /// Someone's oil ran out.
+    pub over: bool,
+}
+
+impl Replay {
+    pub fn new(settings: DifficultySettings, players: usize) -> Self {
+        Self {
+            players: PLAYER_STATIONS
+                .iter()
+                .take(players)
+                .map(|&station| ReplayPlayer::new(station, &settings))
+                .collect(),
+            main_hour: Quat::IDENTITY,
+            main_minute: Quat::IDENTITY,
+            clocks: vec![ReplayClock::spawn()],
+            settings,
+            score: 0.0,
+            breakdown: ScoreBreakdown::default(),
+            ticks: 0,
+            over: false,
+        }
+    }
+
+    /// Play back a whole run from every player's inputs,
+    /// stopping early if the oil or any of the logs runs out.
+    pub fn run(logs: &[&InputLog], settings: DifficultySettings) -> Self {
+        let mut replay = Self::new(settings, logs.len());
+        let mut inputs: Vec<_> = logs.iter().map(|log| log.iter()).collect();
+        while !replay.over {
+            let Some(tick) = inputs
+                .iter_mut()
+                .map(Iterator::next)
+                .collect::<Option<Vec<_>>>()
+            else {
+                break;
+            };
+            replay.step(&tick);
+        }
+        replay
+    }
+
+    /// Advance by one tick, with one input per player, in the same order as the game systems.
+    pub fn step(&mut self, inputs: &[TickInput]) {
+        let dt = TICK_SECS;
+        self.ticks += 1;
+
+        for (player, &input) in inputs.iter().enumerate() {
+            self.step_movement(player, input);
+            self.step_controller(player, input, dt);
+        }
+        for (player, &input) in inputs.iter().enumerate() {
+            self.step_oil(player, input, dt);
+            if self.over {
+                return;
+            }
+        }
+        for player in 0..self.players.len() {
+            self.step_clock_control(player, dt);
+        }
+        self.step_clocks(dt);
+    }
+
+    /// Whether another player than `player` holds `clock`.
+    fn held_by_other(&self, player: usize, clock: usize) -> bool {
+        self.players
+            .iter()
+            .enumerate()
+            .any(|(other, state)| other != player && state.held_clock == Some(clock))
+    }
+
+    /// As `game::movement::movement`.
+    fn step_movement(&mut self, player: usize, input: TickInput) {
+        let direction = walk_direction(input);
+        let state = &mut self.players[player];
+        state.station = walk(state.station, direction);
+        let station = state.station;
+        let position = STATIONS[station];
+
+        if input.pressed(TickInput::ACTION) && station != OIL_STATION {
+            if self.players[player].held_clock.is_some() {
+                let here = self.clocks.iter().filter(|c| c.x == position.x).count();
+                if can_put_down(station, here) {
+                    self.players[player].held_clock = None;
+                }
+            } else {
+                let free = (0..self.clocks.len()).find(|&clock| {
+                    self.clocks[clock].x == position.x && !self.held_by_other(player, clock)
+                });
+                self.players[player].held_clock = free;
+            }
+        }
+
+        if direction != 0.0 {
+            if let Some(held) = self.players[player].held_clock {
+                self.clocks[held].x = position.x;
+            }
+        }
+    }
+
+    /// As `record_clock_controller` in `game::spawn::clock`.
+    fn step_controller(&mut self, player: usize, input: TickInput, dt: f32) {
+        let state = &mut self.players[player];
+        let set = input.held(TickInput::SET);
+        track_hold(&mut state.setting, &mut state.time_setting, set, dt);
+        let wound = input.held(TickInput::WIND);
+        track_hold(&mut state.winding, &mut state.time_winding, wound, dt);
+    }
+
+    /// As `oil_drink` and `oil_leak` in `game::spawn::player`.
+    fn step_oil(&mut self, player: usize, input: TickInput, dt: f32) {
+        let state = &mut self.players[player];
+        if drinks(state.station, state.held_clock.is_some(), input) {
+            state.oil_level = drink(state.oil_level, dt);
+        }
+        let settings = &self.settings;
+        if !leak(
+            &mut state.oil_level,
+            &mut state.oil_leak,
+            &mut state.leak_secs,
+            settings,
+            dt,
+        ) {
+            self.over = true;
+        }
+    }
+
+    /// As `apply_clock_control` in `game::spawn::clock`.
+    fn step_clock_control(&mut self, player: usize, dt: f32) {
+        let state = &mut self.players[player];
+        let Some(held) = state.held_clock else {
+            return;
+        };
+        if state.winding {
+            state.time_winding = clock_hold_secs(state.time_winding, dt);
+        }
+        if state.setting {
+            state.time_setting = clock_hold_secs(state.time_setting, dt);
+        }
+
+        let clock = &mut self.clocks[held];
+        if state.winding {
+            clock.time_left = wind(clock.time_left, dt);
+        }
+        if state.setting {
+            let (hour, minute) = set_turn(state.time_setting, dt);
+            clock.hour = Quat::from_rotation_z(hour) * clock.hour;
+            clock.minute = Quat::from_rotation_z(minute) * clock.minute;
+        }
+    }
+
+    /// As `tick_clocks` and `score_clocks` in `game::spawn::clock`.
+    fn step_clocks(&mut self, dt: f32) {
+        let (hour, minute) = run_turn(1.0, dt);
+        let hour_turn = Quat::from_rotation_z(hour);
+        let minute_turn = Quat::from_rotation_z(minute);
+        self.main_hour = hour_turn * self.main_hour;
+        self.main_minute = minute_turn * self.main_minute;
+        for clock in &mut self.clocks {
+            clock.time_left = run_down(clock.time_left, dt);
+            if clock.time_left == 0.0 {
+                continue;
+            }
+            clock.hour = hour_turn * clock.hour;
+            clock.minute = minute_turn * clock.minute;
+        }
+
+        let main_hour = self.main_hour.normalize();
+        let main_minute = self.main_minute.normalize();
+        let points = dt * self.settings.score_multiplier;
+        for clock in &self.clocks {
+            if clock.time_left <= 0.0 {
+                continue;
+            }
+            let hour_diff = main_hour.angle_between(clock.hour.normalize());
+            let minute_diff = main_minute.angle_between(clock.minute.normalize());
+            let synced = in_sync(hour_diff, minute_diff, &self.settings);
+            let earned = score_clock(self.score, points, synced, false);
+            earned.add_to(&mut self.score);
+            self.breakdown += earned;
+        }
+
+        let threshold = self.settings.spawn_threshold(self.clocks.len());
+        if threshold.is_some_and(|threshold| self.score > threshold) {
+            self.clocks.push(ReplayClock::spawn());
+        }
+    }
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+
+    const IDLE: TickInput = TickInput {
+        held: 0,
+        pressed: 0,
+    };
+
+    /// A solo run on normal rules: walk to the clock spawn, pick up the clock,
+    /// wind it for a second, then wait for the oil to run out.
+    pub(in crate::leaderboard) fn wind_one_clock(settings: &DifficultySettings) -> InputLog {
+        let mut replay = Replay::new(settings.clone(), 1);
+        let mut log = InputLog::default();
+        while !replay.over {
+            let input = match replay.ticks {
+                0 => TickInput {
+                    held: TickInput::LEFT,
+                    pressed: TickInput::LEFT,
+                },
+                1 => TickInput {
+                    held: TickInput::ACTION,
+                    pressed: TickInput::ACTION,
+                },
+                2..=65 => TickInput {
+                    held: TickInput::WIND,
+                    pressed: 0,
+                },
+                _ => IDLE,
+            };
+            log.push(input);
+            replay.step(&[input]);
+        }
+        log
+    }
+
+    #[test]
+    fn input_logs_are_run_length_encoded() {
+        let wind = TickInput {
+            held: TickInput::WIND,
+            pressed: 0,
+        };
+        let mut log = InputLog::default();
+        for input in [IDLE, IDLE, IDLE, wind, wind, IDLE] {
+            log.push(input);
+        }
+        assert_eq!(
+            log.0,
+            vec![[3, 0, 0], [2, TickInput::WIND as u32, 0], [1, 0, 0]]
+        );
+        assert_eq!(log.ticks(), Some(6));
+        assert_eq!(
+            log.iter().collect::<Vec<_>>(),
+            vec![IDLE, IDLE, IDLE, wind, wind, IDLE]
+        );
+    }
+
+    #[test]
+    fn input_log_hashes_follow_the_inputs() {
+        let mut log = InputLog::default();
+        log.push(IDLE);
+        let mut same = InputLog::default();
+        same.push(IDLE);
+        assert_eq!(log.hash(), same.hash());
+        assert_eq!(log.hash().len(), 64);
+
+        same.push(IDLE);
+        assert_ne!(log.hash(), same.hash());
+    }
+
+    #[test]
+    fn overflowing_input_logs_have_no_length() {
+        assert_eq!(InputLog(vec![[u32::MAX, 0, 0]]).ticks(), Some(u32::MAX));
+        assert_eq!(InputLog(vec![[u32::MAX, 0, 0], [1, 0, 0]]).ticks(), None);
+        assert_eq!(
+            InputLog(vec![[u32::MAX / 2 + 1, 0, 0], [u32::MAX / 2 + 1, 1, 0]]).ticks(),
+            None
+        );
+    }
+
+    #[test]
+    fn idle_runs_end_when_the_oil_runs_out() {
+        let mut log = InputLog::default();
+        for _ in 0..(120.0 * TICK_HZ) as u32 {
+            log.push(IDLE);
+        }
+        let replay = Replay::run(&[&log], DifficultySettings::default());
+        assert!(replay.over);
+        assert_eq!(replay.score, 0.0);
+        // 100 oil leaking at 1.4 per second, and 0.01 more every second.
+        let secs = (-1.4 + (1.4_f32.powi(2) + 2.0).sqrt()) / 0.01;
+        let ticks = replay.ticks as f32 * TICK_SECS;
+        assert!((ticks - secs).abs() < 0.1, "{ticks}s, expected {secs}s");
+    }
+
+    #[test]
+    fn replays_a_known_run_to_its_score() {
+        let settings = DifficultySettings::default();
+        let log = wind_one_clock(&settings);
+        let replay = Replay::run(&[&log], settings.clone());
+        assert!(replay.over);
+        assert_eq!(Some(replay.ticks), log.ticks());
+
+        // A second of winding runs the clock for six, in sync the whole time.
+        let running = WIND_RATE;
+        assert!((replay.breakdown.running - running).abs() < 2.0 * TICK_SECS);
+        assert!((replay.breakdown.synced - running).abs() < 2.0 * TICK_SECS);
+        assert_eq!(
+            replay.score,
+            replay.breakdown.running + replay.breakdown.synced
+        );
+
+        let again = Replay::run(&[&log], settings);
+        assert_eq!(again.score, replay.score);
+        assert_eq!(again.ticks, replay.ticks);
+    }
+
+    #[test]
+    fn replays_stop_when_the_inputs_do() {
+        let mut log = InputLog::default();
+        for _ in 0..10 {
+            log.push(IDLE);
+        }
+        let replay = Replay::run(&[&log], DifficultySettings::default());
+        assert!(!replay.over);
+        assert_eq!(replay.ticks, 10);
+    }
+}
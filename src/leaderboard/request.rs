@@ -0,0 +1,258 @@
+//! Tracks in-flight leaderboard requests, timing them out and retrying them
+//! with exponential backoff.
+
+use bevy::prelude::*;
+
+use super::{
+    storage, Leaderboard, LeaderboardBody, LeaderboardFailed, LeaderboardRecord,
+    LeaderboardRequest, ScoreFilter, ScoreSubmitted, ScoresFetched,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<LeaderboardStatus>();
+    app.insert_resource(OwnScores::load());
+    app.observe(retry_failed);
+    app.add_systems(Update, drive_requests);
+}
+
+/// Give up on a request that hasn't answered after this long.
+const REQUEST_TIMEOUT_SECS: f32 = 10.0;
+/// How often a request is tried before asking the player to retry.
+const MAX_ATTEMPTS: u32 = 3;
+/// Wait before the first retry. Doubles with every further attempt.
+const BACKOFF_BASE_SECS: f32 = 1.0;
+
+#[derive(Debug, Clone, Default)]
+pub enum RequestState {
+    #[default]
+    Idle,
+    Loading {
+        attempt: u32,
+        timeout: Timer,
+    },
+    /// The last attempt failed, another one starts when `backoff` finishes.
+    Waiting {
+        attempt: u32,
+        backoff: Timer,
+        error: String,
+    },
+    /// All attempts failed.
+    Failed(String),
+    Done,
+}
+
+impl RequestState {
+    fn loading(attempt: u32) -> Self {
+        Self::Loading {
+            attempt,
+            timeout: Timer::from_seconds(REQUEST_TIMEOUT_SECS, TimerMode::Once),
+        }
+    }
+
+    /// Move on after a failed attempt.
+    fn fail(&mut self, error: String) {
+        let attempt = match self {
+            Self::Loading { attempt, .. } => *attempt,
+            // Not waiting on anything, e.g. a late answer to a timed out request.
+            _ => return,
+        };
+        *self = if attempt < MAX_ATTEMPTS {
+            let backoff = BACKOFF_BASE_SECS * 2.0_f32.powi(attempt as i32 - 1);
+            Self::Waiting {
+                attempt,
+                backoff: Timer::from_seconds(backoff, TimerMode::Once),
+                error,
+            }
+        } else {
+            Self::Failed(error)
+        };
+    }
+
+    pub fn is_failed(&self) -> bool {
+        matches!(self, Self::Failed(_))
+    }
+}
+
+/// The state of the latest fetch and submission.
+#[derive(Resource, Debug, Default)]
+pub struct LeaderboardStatus {
+    pub fetch: RequestState,
+    pub submit: RequestState,
+    /// What is being fetched, kept around for retries.
+    fetch_filter: ScoreFilter,
+    /// The score being submitted, kept around for retries.
+    pub(super) pending_submit: Option<LeaderboardBody>,
+    /// What the player's last score was stored as.
+    submitted: Option<LeaderboardRecord>,
+}
+
+impl LeaderboardStatus {
+    pub(super) fn start_fetch(&mut self, filter: ScoreFilter) {
+        self.fetch = RequestState::loading(1);
+        self.fetch_filter = filter;
+    }
+
+    /// The scores that were last requested.
+    pub fn fetch_filter(&self) -> ScoreFilter {
+        self.fetch_filter
+    }
+
+    pub(super) fn start_submit(&mut self, body: LeaderboardBody) {
+        self.submit = RequestState::loading(1);
+        self.pending_submit = Some(body);
+    }
+
+    /// Forget the last run's score, for a new one to be submitted.
+    pub fn new_run(&mut self) {
+        self.submit = RequestState::Idle;
+        self.submitted = None;
+    }
+
+    /// The id of the player's last stored score, to find it among the fetched ones.
+    pub fn own_id(&self) -> Option<&str> {
+        self.submitted.as_ref().map(|record| record.id.as_str())
+    }
+
+    /// A short description of what the leaderboard is doing, for the player.
+    pub fn message(&self) -> String {
+        let submit = match &self.submit {
+            RequestState::Idle => None,
+            RequestState::Loading { .. } => Some("Submitting score...".to_string()),
+            RequestState::Waiting { backoff, error, .. } => Some(format!(
+                "Couldn't submit score ({error}), retrying in {:.0}s...",
+                backoff.remaining_secs().ceil()
+            )),
+            RequestState::Failed(error) => Some(format!(
+                "Couldn't submit score: {error}\nIt will be sent once the leaderboard is back."
+            )),
+            RequestState::Done => Some("Score submitted!".to_string()),
+        };
+        let fetch = match &self.fetch {
+            RequestState::Idle | RequestState::Done => None,
+            RequestState::Loading { .. } => Some("Loading scores...".to_string()),
+            RequestState::Waiting { backoff, error, .. } => Some(format!(
+                "Couldn't load scores ({error}), retrying in {:.0}s...",
+                backoff.remaining_secs().ceil()
+            )),
+            RequestState::Failed(error) => Some(format!("Couldn't load scores: {error}")),
+        };
+        [submit, fetch]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+const OWN_SCORES_KEY: &str = "own_scores";
+
+/// Ids of every score submitted from this device.
+#[derive(Resource, Debug, Default)]
+pub struct OwnScores(Vec<String>);
+
+impl OwnScores {
+    fn load() -> Self {
+        Self(storage::load(OWN_SCORES_KEY).unwrap_or_default())
+    }
+
+    fn push(&mut self, id: String) {
+        self.0.push(id);
+        storage::save(OWN_SCORES_KEY, &self.0);
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.0.iter().any(|own| own == id)
+    }
+}
+
+/// Trigger this event to try every request that ran out of attempts again.
+#[derive(Event, Debug)]
+pub struct RetryLeaderboard;
+
+fn retry_failed(
+    _trigger: Trigger<RetryLeaderboard>,
+    mut commands: Commands,
+    mut status: ResMut<LeaderboardStatus>,
+    mut leaderboard: ResMut<Leaderboard>,
+) {
+    if status.fetch.is_failed() {
+        let filter = status.fetch_filter;
+        status.start_fetch(filter);
+        leaderboard.fetch_scores(filter, &mut commands);
+    }
+    if status.submit.is_failed() {
+        if let Some(body) = status.pending_submit.clone() {
+            status.start_submit(body.clone());
+            leaderboard.submit_score(body, &mut commands);
+        }
+    }
+}
+
+fn drive_requests(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut status: ResMut<LeaderboardStatus>,
+    mut own_scores: ResMut<OwnScores>,
+    mut leaderboard: ResMut<Leaderboard>,
+    mut ev_fetched: EventReader<ScoresFetched>,
+    mut ev_submitted: EventReader<ScoreSubmitted>,
+    mut ev_failed: EventReader<LeaderboardFailed>,
+) {
+    if ev_fetched.read().any(|fetched| !fetched.cached) {
+        status.fetch = RequestState::Done;
+    }
+    // Queued scores from earlier runs are submitted too, only ours counts.
+    let submitted = status.pending_submit.as_ref().and_then(|body| {
+        ev_submitted
+            .read()
+            .find(|ev| body.matches(&ev.0))
+            .map(|ev| ev.0.clone())
+    });
+    ev_submitted.clear();
+    if let Some(record) = submitted {
+        status.submit = RequestState::Done;
+        own_scores.push(record.id.clone());
+        status.submitted = Some(record);
+        // The scores on screen were fetched before this one was in.
+        let filter = status.fetch_filter;
+        status.start_fetch(filter);
+        leaderboard.fetch_scores(filter, &mut commands);
+    }
+    for failed in ev_failed.read() {
+        warn!("Leaderboard {:?} failed: {}", failed.request, failed.error);
+        match failed.request {
+            LeaderboardRequest::Fetch => status.fetch.fail(failed.error.clone()),
+            LeaderboardRequest::Submit => status.submit.fail(failed.error.clone()),
+        }
+    }
+
+    if let Some(attempt) = tick(&mut status.fetch, &time) {
+        status.fetch = RequestState::loading(attempt);
+        leaderboard.fetch_scores(status.fetch_filter, &mut commands);
+    }
+    if let Some(attempt) = tick(&mut status.submit, &time) {
+        if let Some(body) = status.pending_submit.clone() {
+            status.submit = RequestState::loading(attempt);
+            leaderboard.submit_score(body, &mut commands);
+        }
+    }
+}
+
+/// Advance a request's timers. Returns the attempt number if it should be sent again.
+fn tick(state: &mut RequestState, time: &Time) -> Option<u32> {
+    match state {
+        RequestState::Loading { timeout, .. } => {
+            if timeout.tick(time.delta()).just_finished() {
+                state.fail("timed out".to_string());
+            }
+            None
+        }
+        RequestState::Waiting {
+            attempt, backoff, ..
+        } => backoff
+            .tick(time.delta())
+            .just_finished()
+            .then_some(*attempt + 1),
+        _ => None,
+    }
+}
@@ -0,0 +1,197 @@
+//! Small JSON blobs and JSON Lines logs that survive restarts.
+//! Stored as files in the user's data directory on native,
+//! and in `localStorage` on web.
+
+use bevy::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+
+pub(crate) fn load<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let json = read(key)?;
+    match serde_json::from_str(&json) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            warn!("Ignoring unreadable {key}: {e}");
+            None
+        }
+    }
+}
+
+pub(crate) fn save<T: Serialize>(key: &str, value: &T) {
+    let result = serde_json::to_string(value)
+        .map_err(|e| e.to_string())
+        .and_then(|json| write(key, &json));
+    if let Err(e) = result {
+        error!("Failed to save {key}: {e}");
+    }
+}
+
+/// Every value added to a log with [`append`], skipping unreadable ones.
+pub(crate) fn load_lines<T: DeserializeOwned>(key: &str) -> Vec<T> {
+    let Some(lines) = read_lines(key) else {
+        return Vec::new();
+    };
+    log_values(key, &lines)
+        .into_iter()
+        .filter_map(|value| match serde_json::from_value(value) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                warn!("Ignoring unreadable value in {key}: {e}");
+                None
+            }
+        })
+        .collect()
+}
+
+/// Add a value to the end of a log, without rewriting what's already there.
+/// On web, where the log has to be rewritten, only the last `max_values` are kept.
+pub(crate) fn append<T: Serialize>(key: &str, value: &T, max_values: usize) {
+    let result = serde_json::to_string(value)
+        .map_err(|e| e.to_string())
+        .and_then(|json| append_line(key, &json, max_values));
+    if let Err(e) = result {
+        error!("Failed to append to {key}: {e}");
+    }
+}
+
+/// The values in a log.
+fn log_values(key: &str, lines: &str) -> Vec<serde_json::Value> {
+    let mut values = Vec::new();
+    for value in serde_json::Deserializer::from_str(lines).into_iter() {
+        match value {
+            Ok(value) => values.push(value),
+            Err(e) => {
+                warn!("Ignoring the rest of {key}, it's unreadable: {e}");
+                break;
+            }
+        }
+    }
+    values
+}
+
+/// Where a log is kept, for tools that read it outside the game.
+#[cfg(not(target_family = "wasm"))]
+pub(crate) fn lines_location(key: &str) -> String {
+    match lines_path(key) {
+        Some(path) => path.display().to_string(),
+        None => "nowhere, there is no data directory".to_string(),
+    }
+}
+
+/// The game's directory in the platform's data directory.
+/// Without one, a warning is logged and nothing is kept between runs.
+#[cfg(not(target_family = "wasm"))]
+fn data_dir() -> Option<std::path::PathBuf> {
+    use std::{env, path::PathBuf, sync::Once};
+
+    static WARN_ONCE: Once = Once::new();
+
+    // Relative paths would end up wherever the game was started from.
+    let var = |name| {
+        env::var_os(name)
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+    };
+    let dir = if cfg!(windows) {
+        var("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        var("HOME").map(|home| home.join("Library/Application Support"))
+    } else {
+        var("XDG_DATA_HOME").or_else(|| var("HOME").map(|home| home.join(".local/share")))
+    };
+    if dir.is_none() {
+        WARN_ONCE.call_once(|| warn!("No data directory found, nothing will be saved"));
+    }
+    Some(dir?.join("clockery"))
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn lines_path(key: &str) -> Option<std::path::PathBuf> {
+    path(key).map(|path| path.with_extension("jsonl"))
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn path(key: &str) -> Option<std::path::PathBuf> {
+    Some(data_dir()?.join(format!("{key}.json")))
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn read(key: &str) -> Option<String> {
+    std::fs::read_to_string(path(key)?).ok()
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn write(key: &str, json: &str) -> Result<(), String> {
+    let Some(path) = path(key) else {
+        return Ok(());
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(path, json).map_err(|e| e.to_string())
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn read_lines(key: &str) -> Option<String> {
+    std::fs::read_to_string(lines_path(key)?).ok()
+}
+
+/// Native logs are only ever added to, so `_max_values` isn't needed.
+#[cfg(not(target_family = "wasm"))]
+fn append_line(key: &str, json: &str, _max_values: usize) -> Result<(), String> {
+    use std::io::Write;
+
+    let Some(path) = lines_path(key) else {
+        return Ok(());
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| writeln!(file, "{json}"))
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(target_family = "wasm")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_family = "wasm")]
+fn read(key: &str) -> Option<String> {
+    local_storage()?.get_item(&format!("clockery.{key}")).ok()?
+}
+
+#[cfg(target_family = "wasm")]
+fn write(key: &str, json: &str) -> Result<(), String> {
+    local_storage()
+        .ok_or("localStorage is unavailable")?
+        .set_item(&format!("clockery.{key}"), json)
+        .map_err(|e| format!("{e:?}"))
+}
+
+#[cfg(target_family = "wasm")]
+pub(crate) fn lines_location(key: &str) -> String {
+    format!("localStorage item clockery.{key}")
+}
+
+#[cfg(target_family = "wasm")]
+fn read_lines(key: &str) -> Option<String> {
+    read(key)
+}
+
+/// `localStorage` can't append, so the whole log is written again,
+/// without its oldest values to stay within the quota.
+#[cfg(target_family = "wasm")]
+fn append_line(key: &str, json: &str, max_values: usize) -> Result<(), String> {
+    let mut values = log_values(key, &read(key).unwrap_or_default());
+    values.push(serde_json::from_str(json).map_err(|e| e.to_string())?);
+    let excess = values.len().saturating_sub(max_values);
+    let lines: String = values[excess..]
+        .iter()
+        .map(|value| format!("{value}\n"))
+        .collect();
+    write(key, &lines)
+}
@@ -0,0 +1,406 @@
+//! Signed summaries of finished runs, so the leaderboard can check a score
+//! by playing the run back instead of taking the number on trust.
+//!
+//! The signing key ships with the game, so the signature only stops casual
+//! tampering with the request. The replay is what actually checks the score.
+
+use std::fmt;
+
+use hmac_sha256::{Hash, HMAC};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    replay::{to_hex, InputLog, Replay, ScoreBreakdown, MAX_RUN_TICKS, TICK_SECS},
+    DifficultySettings, LeaderboardBody,
+};
+
+/// Replayed scores may differ this much from the submitted one, as a fraction of it,
+/// to allow for floating point differences between platforms.
+const SCORE_TOLERANCE: f32 = 0.005;
+/// Seconds the submitted run duration may differ from the replayed one.
+const DURATION_TOLERANCE_SECS: f32 = 1.0;
+
+/// Release builds are signed with the leaderboard's own key, so the development one never ships.
+#[cfg(not(debug_assertions))]
+const RUN_KEY: &str = env!(
+    "CLOCKERY_RUN_KEY",
+    "set CLOCKERY_RUN_KEY to the leaderboard's run key to build a release"
+);
+#[cfg(debug_assertions)]
+const RUN_KEY: &str = match option_env!("CLOCKERY_RUN_KEY") {
+    Some(key) => key,
+    None => "clockery-dev-run-key",
+};
+const _: () = assert!(!RUN_KEY.is_empty(), "CLOCKERY_RUN_KEY is empty");
+
+/// The key runs are signed with, from `CLOCKERY_RUN_KEY` when building.
+/// Debug builds fall back to a development key.
+pub fn run_key() -> &'static [u8] {
+    RUN_KEY.as_bytes()
+}
+
+/// Everything needed to play a run back.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RunSummary {
+    /// Picked at random for each run, so no two summaries are alike.
+    pub seed: u64,
+    /// The rules the run was played with.
+    pub settings: DifficultySettings,
+    pub ticks: u32,
+    pub breakdown: ScoreBreakdown,
+    /// SHA-256 of every player's inputs, covered by the signature in their place.
+    pub input_hash: String,
+    pub inputs: InputLog,
+    /// The inputs of the other players, in co-op and versus runs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub other_inputs: Vec<InputLog>,
+    /// HMAC-SHA256 of the score, the run details and this summary.
+    #[serde(default)]
+    pub signature: String,
+}
+
+impl RunSummary {
+    pub fn new(
+        seed: u64,
+        settings: DifficultySettings,
+        breakdown: ScoreBreakdown,
+        inputs: InputLog,
+        other_inputs: Vec<InputLog>,
+    ) -> Self {
+        let mut summary = Self {
+            seed,
+            settings,
+            ticks: inputs.ticks().unwrap_or(u32::MAX),
+            breakdown,
+            input_hash: String::new(),
+            inputs,
+            other_inputs,
+            signature: String::new(),
+        };
+        summary.input_hash = summary.hash_inputs();
+        summary
+    }
+
+    /// Every player's inputs, in player order.
+    pub fn logs(&self) -> Vec<&InputLog> {
+        std::iter::once(&self.inputs)
+            .chain(&self.other_inputs)
+            .collect()
+    }
+
+    /// The hash of every player's log hash, in player order.
+    fn hash_inputs(&self) -> String {
+        let mut hash = Hash::new();
+        for log in self.logs() {
+            hash.update(log.hash());
+        }
+        to_hex(&hash.finalize())
+    }
+}
+
+impl LeaderboardBody {
+    /// Sign the run summary, if there is one.
+    pub fn sign(mut self, key: &[u8]) -> Self {
+        if let Some(signature) = self.signature(key) {
+            if let Some(summary) = &mut self.summary {
+                summary.signature = signature;
+            }
+        }
+        self
+    }
+
+    fn signature(&self, key: &[u8]) -> Option<String> {
+        let summary = self.summary.as_ref()?;
+        let message = serde_json::to_string(&(
+            self.score,
+            &self.run,
+            summary.seed,
+            &summary.settings,
+            summary.ticks,
+            summary.breakdown,
+            &summary.input_hash,
+        ))
+        .ok()?;
+        Some(to_hex(&HMAC::mac(message, key)))
+    }
+
+    /// Check the signature, then play the run back and compare the scores.
+    pub fn verify(&self, key: &[u8]) -> Result<(), VerifyError> {
+        let Some(summary) = &self.summary else {
+            return Err(VerifyError::Unsigned);
+        };
+        if self.signature(key).as_ref() != Some(&summary.signature) {
+            return Err(VerifyError::BadSignature);
+        }
+        if summary.ticks > MAX_RUN_TICKS {
+            return Err(VerifyError::TooLong);
+        }
+        let logs = summary.logs();
+        if logs.iter().any(|log| log.ticks() != Some(summary.ticks))
+            || summary.hash_inputs() != summary.input_hash
+        {
+            return Err(VerifyError::InputsTampered);
+        }
+        if !summary.settings.allowed_for(self.run.difficulty) {
+            return Err(VerifyError::DifficultyMismatch);
+        }
+        if !self.run.ranked() {
+            return Err(VerifyError::Unranked);
+        }
+        if logs.len() != self.run.mode.players() {
+            return Err(VerifyError::ModeMismatch);
+        }
+
+        let replay = Replay::run(&logs, summary.settings.clone());
+        if !replay.over || replay.ticks != summary.ticks {
+            return Err(VerifyError::Unfinished);
+        }
+        let tolerance = self.score.abs() * SCORE_TOLERANCE + TICK_SECS;
+        let breakdown = summary.breakdown;
+        if (replay.score - self.score).abs() > tolerance
+            || (replay.breakdown.running - breakdown.running).abs() > tolerance
+            || (replay.breakdown.synced - breakdown.synced).abs() > tolerance
+        {
+            return Err(VerifyError::ScoreMismatch {
+                submitted: self.score,
+                replayed: replay.score,
+            });
+        }
+        let duration = replay.ticks as f32 * TICK_SECS;
+        if (self.run.duration_secs - duration).abs() > DURATION_TOLERANCE_SECS {
+            return Err(VerifyError::DurationMismatch);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VerifyError {
+    Unsigned,
+    BadSignature,
+    InputsTampered,
+    /// The run is longer than [`MAX_RUN_TICKS`], too long to play back.
+    TooLong,
+    /// The rules don't belong to the difficulty the run claims.
+    DifficultyMismatch,
+    /// The run has a different number of players than its mode.
+    ModeMismatch,
+    /// The mode or difficulty has no leaderboard.
+    Unranked,
+    /// The oil didn't run out when the inputs did.
+    Unfinished,
+    ScoreMismatch {
+        submitted: f32,
+        replayed: f32,
+    },
+    DurationMismatch,
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsigned => write!(f, "run summary is missing"),
+            Self::BadSignature => write!(f, "run signature doesn't match"),
+            Self::InputsTampered => write!(f, "run inputs don't match their hash"),
+            Self::TooLong => write!(f, "run is too long to check"),
+            Self::DifficultyMismatch => write!(f, "run rules don't match its difficulty"),
+            Self::ModeMismatch => write!(f, "run players don't match its mode"),
+            Self::Unranked => write!(f, "run isn't ranked"),
+            Self::Unfinished => write!(f, "run doesn't end when the oil runs out"),
+            Self::ScoreMismatch {
+                submitted,
+                replayed,
+            } => write!(
+                f,
+                "submitted score {submitted:.2} doesn't match the replayed {replayed:.2}"
+            ),
+            Self::DurationMismatch => write!(f, "run duration doesn't match its inputs"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::leaderboard::{
+        replay::{tests::wind_one_clock, TickInput},
+        Difficulty, GameMode, RunInfo,
+    };
+
+    const KEY: &[u8] = b"test-run-key";
+
+    /// A signed normal solo run, as the game submits it.
+    fn body() -> LeaderboardBody {
+        let settings = DifficultySettings::default();
+        let inputs = wind_one_clock(&settings);
+        let replay = Replay::run(&[&inputs], settings.clone());
+        LeaderboardBody {
+            name: "Tockery".to_string(),
+            score: replay.score,
+            run: RunInfo::finished(
+                GameMode::Solo,
+                Difficulty::Normal,
+                replay.ticks as f32 * TICK_SECS,
+            ),
+            summary: Some(RunSummary::new(
+                7,
+                settings,
+                replay.breakdown,
+                inputs,
+                Vec::new(),
+            )),
+        }
+        .sign(KEY)
+    }
+
+    /// Change the body, then sign it again, as someone with the key could.
+    fn resigned(change: impl FnOnce(&mut LeaderboardBody)) -> LeaderboardBody {
+        let mut body = body();
+        change(&mut body);
+        body.sign(KEY)
+    }
+
+    fn summary(body: &mut LeaderboardBody) -> &mut RunSummary {
+        body.summary.as_mut().unwrap()
+    }
+
+    #[test]
+    fn signed_runs_verify() {
+        assert_eq!(body().verify(KEY), Ok(()));
+    }
+
+    #[test]
+    fn unsigned_runs_are_rejected() {
+        let mut body = body();
+        body.summary = None;
+        assert_eq!(body.verify(KEY), Err(VerifyError::Unsigned));
+    }
+
+    #[test]
+    fn tampered_signatures_are_rejected() {
+        assert_eq!(body().verify(b"other-key"), Err(VerifyError::BadSignature));
+
+        let mut body = body();
+        summary(&mut body).signature.replace_range(..1, "x");
+        assert_eq!(body.verify(KEY), Err(VerifyError::BadSignature));
+    }
+
+    #[test]
+    fn tampered_scores_are_rejected() {
+        let mut body = body();
+        body.score += 10.0;
+        assert_eq!(body.verify(KEY), Err(VerifyError::BadSignature));
+
+        let body = resigned(|body| body.score += 10.0);
+        assert!(matches!(
+            body.verify(KEY),
+            Err(VerifyError::ScoreMismatch { .. })
+        ));
+
+        let body = resigned(|body| summary(body).breakdown.synced += 10.0);
+        assert!(matches!(
+            body.verify(KEY),
+            Err(VerifyError::ScoreMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn tampered_inputs_are_rejected() {
+        // Same length, different inputs.
+        let mut body = body();
+        let run = summary(&mut body);
+        let wound: Vec<_> = run
+            .inputs
+            .iter()
+            .map(|input| TickInput {
+                held: input.held | TickInput::WIND,
+                ..input
+            })
+            .collect();
+        run.inputs = InputLog::default();
+        for input in wound {
+            run.inputs.push(input);
+        }
+        assert_eq!(body.verify(KEY), Err(VerifyError::InputsTampered));
+
+        // Longer than the summary says.
+        let mut body = self::body();
+        summary(&mut body).inputs.push(TickInput::default());
+        assert_eq!(body.verify(KEY), Err(VerifyError::InputsTampered));
+    }
+
+    #[test]
+    fn overflowing_inputs_are_rejected() {
+        let overflowing: InputLog = serde_json::from_str("[[4294967295,0,0],[1,0,0]]").unwrap();
+
+        let body = resigned(|body| summary(body).inputs = overflowing.clone());
+        assert_eq!(body.verify(KEY), Err(VerifyError::InputsTampered));
+
+        let body = resigned(|body| {
+            let summary = summary(body);
+            *summary = RunSummary::new(
+                summary.seed,
+                summary.settings.clone(),
+                summary.breakdown,
+                overflowing,
+                Vec::new(),
+            );
+        });
+        assert_eq!(body.verify(KEY), Err(VerifyError::TooLong));
+    }
+
+    #[test]
+    fn overlong_runs_are_rejected() {
+        let body = resigned(|body| summary(body).ticks = MAX_RUN_TICKS + 1);
+        assert_eq!(body.verify(KEY), Err(VerifyError::TooLong));
+    }
+
+    #[test]
+    fn rules_must_match_the_difficulty() {
+        let body = resigned(|body| body.run.difficulty = Difficulty::Hard);
+        assert_eq!(body.verify(KEY), Err(VerifyError::DifficultyMismatch));
+    }
+
+    #[test]
+    fn unranked_runs_are_rejected() {
+        let body = resigned(|body| {
+            body.run.difficulty = Difficulty::Custom;
+            summary(body).settings = DifficultySettings::default().custom();
+        });
+        assert_eq!(body.verify(KEY), Err(VerifyError::Unranked));
+
+        let body = resigned(|body| body.run.mode = GameMode::Versus);
+        assert_eq!(body.verify(KEY), Err(VerifyError::Unranked));
+    }
+
+    #[test]
+    fn players_must_match_the_mode() {
+        let body = resigned(|body| body.run.mode = GameMode::Coop);
+        assert_eq!(body.verify(KEY), Err(VerifyError::ModeMismatch));
+    }
+
+    #[test]
+    fn runs_must_end_with_their_inputs() {
+        let body = resigned(|body| {
+            let summary = summary(body);
+            let mut inputs = InputLog::default();
+            for input in summary.inputs.iter().take(100) {
+                inputs.push(input);
+            }
+            *summary = RunSummary::new(
+                summary.seed,
+                summary.settings.clone(),
+                summary.breakdown,
+                inputs,
+                Vec::new(),
+            );
+        });
+        assert_eq!(body.verify(KEY), Err(VerifyError::Unfinished));
+    }
+
+    #[test]
+    fn durations_must_match_the_inputs() {
+        let body = resigned(|body| body.run.duration_secs += 5.0);
+        assert_eq!(body.verify(KEY), Err(VerifyError::DurationMismatch));
+    }
+}
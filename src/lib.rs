@@ -1,7 +1,9 @@
 #[cfg(feature = "dev")]
 mod dev_tools;
 mod game;
+pub mod leaderboard;
 mod screen;
+mod tween;
 mod ui;
 
 use bevy::{
@@ -12,6 +14,8 @@ use bevy::{
 };
 use bevy_http_client::HttpClientPlugin;
 
+pub use game::{history, stats};
+
 pub struct AppPlugin;
 
 impl Plugin for AppPlugin {
@@ -22,7 +26,10 @@ impl Plugin for AppPlugin {
             (AppSet::TickTimers, AppSet::RecordInput, AppSet::Update).chain(),
         );
 
-        app.configure_sets(FixedUpdate, AppSet::FixedUpdate);
+        app.configure_sets(
+            FixedUpdate,
+            (AppSet::RecordInput, AppSet::Update, AppSet::FixedUpdate).chain(),
+        );
 
         app.add_systems(Startup, spawn_camera);
 
@@ -55,9 +62,9 @@ impl Plugin for AppPlugin {
         );
 
         // Add other plugins.
-        app.add_plugins((game::plugin, screen::plugin, ui::plugin));
+        app.add_plugins((game::plugin, screen::plugin, tween::plugin, ui::plugin));
 
-        app.add_plugins(HttpClientPlugin);
+        app.add_plugins((HttpClientPlugin, leaderboard::plugin));
 
         // Enable dev tools for dev builds.
         #[cfg(feature = "dev")]
@@ -4,10 +4,7 @@ use bevy::prelude::*;
 
 use super::Screen;
 use crate::{
-    game::{
-        assets::{HandleMap, ImageKey, SoundtrackKey},
-        audio::soundtrack::PlaySoundtrack,
-    },
+    game::assets::{HandleMap, ImageKey},
     ui::prelude::*,
 };
 
@@ -67,22 +64,16 @@ fn enter_credits(mut commands: Commands, image_handles: Res<HandleMap<ImageKey>>
 
             children.button("Back").insert(CreditsAction::Back);
         });
-
-    commands.trigger(PlaySoundtrack::Key(SoundtrackKey::Credits));
 }
 
 fn handle_credits_action(
-    mut commands: Commands,
     mut next_screen: ResMut<NextState<Screen>>,
     mut button_query: InteractionQuery<&CreditsAction>,
 ) {
     for (interaction, action) in &mut button_query {
         if matches!(interaction, Interaction::Pressed) {
             match action {
-                CreditsAction::Back => {
-                    next_screen.set(Screen::Title);
-                    commands.trigger(PlaySoundtrack::Key(SoundtrackKey::Menu));
-                }
+                CreditsAction::Back => next_screen.set(Screen::Title),
             }
         }
     }
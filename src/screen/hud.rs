@@ -0,0 +1,241 @@
+//! The in-game HUD: score and how fast it's going up, the oil left and how fast
+//! it's leaking, the clocks on the bench and how far the next one is.
+//! The clock thresholds come from the rules of the run.
+//! In versus each bench has a panel of its own.
+
+use bevy::prelude::*;
+
+use super::Screen;
+use crate::{
+    game::{
+        difficulty::{DifficultyChoice, RunRules},
+        run::run_in_progress,
+        spawn::{
+            bench::{bench_count, Bench, Score},
+            clock::{ClockController, Interactable},
+            player::{Player, PLAYER_NAMES},
+        },
+    },
+    leaderboard::replay::MAX_OIL,
+    tween::{Tween, POP_IN_SCALE},
+    ui::prelude::*,
+    AppSet,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ScoreRate>();
+    app.add_systems(OnEnter(Screen::Playing), spawn_hud);
+    app.add_systems(
+        FixedUpdate,
+        measure_score_rate
+            .after(AppSet::FixedUpdate)
+            .run_if(run_in_progress)
+            .run_if(in_state(Screen::Playing)),
+    );
+    app.add_systems(
+        Update,
+        update_hud
+            .in_set(AppSet::Update)
+            .run_if(in_state(Screen::Playing)),
+    );
+}
+
+const HUD_WIDTH: f32 = 300.0;
+
+/// Points scored per second on the last tick, on each bench.
+#[derive(Resource, Debug, Default)]
+struct ScoreRate {
+    per_sec: Vec<f32>,
+    /// Each bench's score after the last tick.
+    last: Vec<f32>,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+enum HudValue {
+    Score,
+    Oil,
+    Leak,
+    Clocks,
+    NextClock,
+}
+
+impl HudValue {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Score => "Score",
+            Self::Oil => "Oil",
+            Self::Leak => "Leak",
+            Self::Clocks => "Clocks",
+            Self::NextClock => "Next clock",
+        }
+    }
+}
+
+fn spawn_hud(mut commands: Commands, mut rate: ResMut<ScoreRate>, choice: Res<DifficultyChoice>) {
+    let benches = bench_count(choice.mode);
+    rate.per_sec = vec![0.0; benches];
+    rate.last = vec![0.0; benches];
+    for bench in 0..benches {
+        spawn_panel(&mut commands, benches > 1, bench);
+    }
+}
+
+/// The first of two benches has its panel on the left.
+fn spawn_panel(commands: &mut Commands, versus: bool, bench: usize) {
+    let (left, right) = if versus && bench == 0 {
+        (Val::Px(20.0), Val::Auto)
+    } else {
+        (Val::Auto, Val::Px(20.0))
+    };
+    commands
+        .spawn((
+            Name::new("HUD"),
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left,
+                    right,
+                    top: Val::Px(20.0),
+                    width: Val::Px(HUD_WIDTH),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(4.0),
+                    padding: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                background_color: ui_palette::NODE_BACKGROUND.with_alpha(0.4).into(),
+                transform: Transform::from_scale(Vec3::splat(POP_IN_SCALE)),
+                ..default()
+            },
+            Tween::pop_in(),
+            StateScoped(Screen::Playing),
+        ))
+        .with_children(|children| {
+            if versus {
+                children.spawn(hud_text(PLAYER_NAMES[bench], ui_palette::HEADER_TEXT));
+            }
+            for value in [
+                HudValue::Score,
+                HudValue::Oil,
+                HudValue::Leak,
+                HudValue::Clocks,
+                HudValue::NextClock,
+            ] {
+                children
+                    .spawn(NodeBundle {
+                        style: Style {
+                            justify_content: JustifyContent::SpaceBetween,
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|children| {
+                        children.spawn(hud_text(value.label(), ui_palette::LABEL_TEXT));
+                        children.spawn((
+                            hud_text("", ui_palette::BUTTON_TEXT),
+                            value,
+                            Bench(bench),
+                        ));
+                    });
+            }
+            children
+                .progress_bar(HUD_WIDTH - 20.0)
+                .insert((HudValue::NextClock, Bench(bench)));
+        });
+}
+
+fn hud_text(text: &str, color: Color) -> impl Bundle {
+    (
+        TextBundle::from_section(
+            text,
+            TextStyle {
+                font_size: ui_palette::HUD_FONT_SIZE,
+                color,
+                ..default()
+            },
+        ),
+        ScaledText(ui_palette::HUD_FONT_SIZE),
+    )
+}
+
+fn measure_score_rate(
+    time: Res<Time>,
+    mut rate: ResMut<ScoreRate>,
+    bench_query: Query<(&Bench, &Score)>,
+) {
+    let dt = time.delta_seconds();
+    if dt <= 0.0 {
+        return;
+    }
+    let rate = &mut *rate;
+    for (bench, score) in &bench_query {
+        let (Some(per_sec), Some(last)) =
+            (rate.per_sec.get_mut(bench.0), rate.last.get_mut(bench.0))
+        else {
+            continue;
+        };
+        *per_sec = (score.points - *last) / dt;
+        *last = score.points;
+    }
+}
+
+fn update_hud(
+    rate: Res<ScoreRate>,
+    rules: Res<RunRules>,
+    bench_query: Query<(&Bench, &Score)>,
+    player_query: Query<(&Player, &Bench, &ClockController)>,
+    clock_query: Query<&Bench, With<Interactable>>,
+    mut text_query: Query<(&HudValue, &Bench, &mut Text)>,
+    mut bar_query: Query<(&Bench, &mut ProgressBar), With<HudValue>>,
+) {
+    for (&bench, score) in &bench_query {
+        let mut players: Vec<_> = player_query
+            .iter()
+            .filter(|(_, on, _)| **on == bench)
+            .collect();
+        players.sort_by_key(|(player, ..)| player.0);
+        let Some(&(_, _, controller)) = players.first() else {
+            continue;
+        };
+        let score = score.points;
+        let rate = rate.per_sec.get(bench.0).copied().unwrap_or_default();
+        let clocks = clock_query.iter().filter(|on| **on == bench).count();
+        let next = rules.spawn_threshold(clocks);
+        let previous = clocks
+            .checked_sub(2)
+            .and_then(|index| rules.spawn_thresholds.get(index))
+            .copied()
+            .unwrap_or(0.0);
+
+        for (value, _, mut text) in text_query.iter_mut().filter(|(_, on, _)| **on == bench) {
+            let value = match value {
+                HudValue::Score => format!("{score:.2} ({rate:+.2}/s)"),
+                HudValue::Oil => players
+                    .iter()
+                    .map(|(.., controller)| {
+                        format!("{:.0}%", controller.oil_level / MAX_OIL * 100.0)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" / "),
+                HudValue::Leak => format!("{:.2}/s", controller.oil_leak),
+                HudValue::Clocks => format!("{clocks} / {}", rules.max_clocks()),
+                HudValue::NextClock => match next {
+                    Some(next) => format!("{score:.0} / {next:.0}"),
+                    None => "All out".to_string(),
+                },
+            };
+            if text.sections[0].value != value {
+                text.sections[0].value = value;
+            }
+        }
+
+        let progress = match next {
+            Some(next) if next > previous => (score - previous) / (next - previous),
+            _ => 1.0,
+        };
+        for (_, mut bar) in bar_query.iter_mut().filter(|(on, _)| **on == bench) {
+            if bar.0 != progress {
+                bar.0 = progress;
+            }
+        }
+    }
+}
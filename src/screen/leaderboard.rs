@@ -1,23 +1,30 @@
-//! A loading screen during which game assets are loaded.
-//! This reduces stuttering, especially for audio on WASM.
+//! The leaderboard screen, and the leaderboard status widgets shared with the game over panel.
 
 use bevy::prelude::*;
-use bevy_http_client::prelude::{TypedRequest, TypedResponse};
 
-use super::{
-    credits::CreditsAction,
-    playing::{get_scores, LeaderboardRecord},
-    PlayingState, Screen,
-};
+use super::{credits::CreditsAction, Screen};
 use crate::{
-    game::{
-        assets::{HandleMap, ImageKey, SoundtrackKey},
-        audio::soundtrack::PlaySoundtrack,
+    game::assets::{HandleMap, ImageKey},
+    leaderboard::{
+        Difficulty, FetchScores, GameMode, LeaderboardRecord, LeaderboardStatus, OwnScores,
+        RetryLeaderboard, ScoreCache, ScoreFilter, ScoresFetched, TimeWindow,
     },
     ui::prelude::*,
 };
 
 pub(super) fn plugin(app: &mut App) {
+    app.register_type::<LeaderboardAction>();
+    app.init_resource::<LeaderboardTab>();
+    app.add_systems(
+        Update,
+        (
+            update_status_text.run_if(
+                resource_changed::<LeaderboardStatus>.or_else(resource_changed::<ScoreCache>),
+            ),
+            handle_leaderboard_action,
+        ),
+    );
+
     app.add_systems(OnEnter(Screen::Leaderboard), enter_leaderboard);
     app.add_systems(
         Update,
@@ -26,16 +33,20 @@ pub(super) fn plugin(app: &mut App) {
 
     app.add_systems(
         Update,
-        handle_response.run_if(in_state(Screen::Leaderboard)),
+        (handle_response, highlight_selected_tab).run_if(in_state(Screen::Leaderboard)),
     );
 }
 
+/// The category shown on the leaderboard screen.
+#[derive(Resource, Debug, Default, Deref, DerefMut)]
+struct LeaderboardTab(ScoreFilter);
+
 fn enter_leaderboard(
     mut commands: Commands,
-    mut ev_request: EventWriter<TypedRequest<Vec<LeaderboardRecord>>>,
     image_handles: Res<HandleMap<ImageKey>>,
+    tab: Res<LeaderboardTab>,
 ) {
-    get_scores(ev_request);
+    commands.trigger(FetchScores(tab.0));
 
     commands.spawn((
         SpriteBundle {
@@ -58,7 +69,7 @@ fn enter_leaderboard(
             text: Text::from_section(
                 "Leaderboard",
                 TextStyle {
-                    font_size: 30.0,
+                    font_size: ui_palette::BODY_FONT_SIZE,
                     color: Color::WHITE,
                     ..default()
                 },
@@ -73,6 +84,7 @@ fn enter_leaderboard(
             },
             ..default()
         },
+        ScaledText(ui_palette::BODY_FONT_SIZE),
         StateScoped(Screen::Leaderboard),
     ));
 
@@ -80,14 +92,44 @@ fn enter_leaderboard(
         .ui_root()
         .insert(StateScoped(Screen::Leaderboard))
         .with_children(|children| {
+            let modes = GameMode::RANKED.map(|mode| (mode.label(), LeaderboardAction::Mode(mode)));
+            let difficulties = Difficulty::RANKED.map(|difficulty| {
+                (
+                    difficulty.label(),
+                    LeaderboardAction::Difficulty(difficulty),
+                )
+            });
+            let windows =
+                TimeWindow::ALL.map(|window| (window.label(), LeaderboardAction::Window(window)));
+            let tab_rows: [Vec<_>; 2] = [
+                modes.into_iter().chain(difficulties).collect(),
+                windows.into(),
+            ];
+            for tabs in tab_rows {
+                children
+                    .spawn(NodeBundle {
+                        style: Style {
+                            column_gap: Val::Px(10.0),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|children| {
+                        for (label, action) in tabs {
+                            children.small_button(label).insert(action);
+                        }
+                    });
+            }
+            children.spawn(status_text(0.0));
+            children.score_list(10);
+            children
+                .button("Retry")
+                .insert(LeaderboardAction::Retry)
+                .insert(Visibility::Hidden);
             children
                 .button("Back")
                 .insert(CreditsAction::Back)
                 .insert(Style {
-                    margin: UiRect {
-                        top: Val::Px(600.0),
-                        ..default()
-                    },
                     padding: UiRect {
                         top: Val::Px(10.0),
                         bottom: Val::Px(10.0),
@@ -100,52 +142,165 @@ fn enter_leaderboard(
 }
 
 fn handle_credits_action(
-    mut commands: Commands,
     mut next_screen: ResMut<NextState<Screen>>,
     mut button_query: InteractionQuery<&CreditsAction>,
 ) {
     for (interaction, action) in &mut button_query {
         if matches!(interaction, Interaction::Pressed) {
             match action {
-                CreditsAction::Back => {
-                    next_screen.set(Screen::Title);
-                    commands.trigger(PlaySoundtrack::Key(SoundtrackKey::Menu));
-                }
+                CreditsAction::Back => next_screen.set(Screen::Title),
             }
         }
     }
 }
 
-fn handle_response(
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub(super) enum LeaderboardAction {
+    Retry,
+    Mode(GameMode),
+    Difficulty(Difficulty),
+    Window(TimeWindow),
+}
+
+/// Shows what the leaderboard is doing, e.g. loading or failing.
+#[derive(Component)]
+pub(super) struct LeaderboardStatusText;
+
+/// The rows for `records`, marking the player's own scores.
+pub(super) fn score_rows(
+    records: &[LeaderboardRecord],
+    own_scores: &OwnScores,
+    status: &LeaderboardStatus,
+) -> Vec<ScoreRow> {
+    records
+        .iter()
+        .enumerate()
+        .map(|(i, record)| ScoreRow {
+            rank: i + 1,
+            name: record.name.clone(),
+            score: record.score,
+            own: own_scores.contains(&record.id),
+            latest: status.own_id() == Some(record.id.as_str()),
+        })
+        .collect()
+}
+
+pub(super) fn status_text(top: f32) -> impl Bundle {
+    (
+        TextBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: ui_palette::STATUS_FONT_SIZE,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            )
+            .with_justify(JustifyText::Center),
+            style: Style {
+                justify_self: JustifySelf::Center,
+                margin: UiRect {
+                    top: Val::Px(top),
+                    ..default()
+                },
+                ..default()
+            },
+            ..default()
+        },
+        ScaledText(ui_palette::STATUS_FONT_SIZE),
+        LeaderboardStatusText,
+    )
+}
+
+fn update_status_text(
+    status: Res<LeaderboardStatus>,
+    cache: Res<ScoreCache>,
+    mut text_query: Query<&mut Text, With<LeaderboardStatusText>>,
+    mut retry_query: Query<(&mut Visibility, &LeaderboardAction)>,
+) {
+    let mut message = status.message();
+    if status.fetch.is_failed() {
+        if let Some(last_updated) = cache.last_updated() {
+            message.push('\n');
+            message.push_str(&last_updated);
+        }
+    }
+    for mut text in &mut text_query {
+        text.sections[0].value.clone_from(&message);
+    }
+
+    let failed = status.fetch.is_failed() || status.submit.is_failed();
+    for (mut visibility, action) in &mut retry_query {
+        if *action == LeaderboardAction::Retry {
+            *visibility = if failed {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+        }
+    }
+}
+
+fn handle_leaderboard_action(
     mut commands: Commands,
-    mut ev_response: EventReader<TypedResponse<Vec<LeaderboardRecord>>>,
+    mut button_query: InteractionQuery<&LeaderboardAction>,
+    mut tab: ResMut<LeaderboardTab>,
+) {
+    for (interaction, action) in &mut button_query {
+        if !matches!(interaction, Interaction::Pressed) {
+            continue;
+        }
+        match *action {
+            LeaderboardAction::Retry => {
+                commands.trigger(RetryLeaderboard);
+                continue;
+            }
+            LeaderboardAction::Mode(mode) => tab.mode = mode,
+            LeaderboardAction::Difficulty(difficulty) => tab.difficulty = difficulty,
+            LeaderboardAction::Window(window) => tab.window = window,
+        }
+        commands.trigger(FetchScores(tab.0));
+    }
+}
+
+/// Show which category is selected.
+fn highlight_selected_tab(
+    tab: Res<LeaderboardTab>,
+    mut button_query: Query<(
+        &LeaderboardAction,
+        &mut InteractionPalette,
+        &mut BackgroundColor,
+    )>,
+) {
+    for (action, mut palette, mut background) in &mut button_query {
+        let selected = match *action {
+            LeaderboardAction::Retry => false,
+            LeaderboardAction::Mode(mode) => tab.mode == mode,
+            LeaderboardAction::Difficulty(difficulty) => tab.difficulty == difficulty,
+            LeaderboardAction::Window(window) => tab.window == window,
+        };
+        let none = if selected {
+            ui_palette::BUTTON_SELECTED_BACKGROUND
+        } else {
+            ui_palette::NODE_BACKGROUND
+        };
+        if palette.none != none {
+            palette.none = none;
+            *background = none.into();
+        }
+    }
+}
+
+fn handle_response(
+    mut ev_response: EventReader<ScoresFetched>,
+    status: Res<LeaderboardStatus>,
+    own_scores: Res<OwnScores>,
+    mut list_query: Query<&mut ScoreList>,
 ) {
     for res in ev_response.read() {
-        for (i, score) in res.iter().take(25).enumerate() {
-            let text = format!("{}. {} - {:.2}", i + 1, score.name, score.score);
-            commands.spawn((
-                TextBundle {
-                    text: Text::from_section(
-                        text,
-                        TextStyle {
-                            font_size: 20.0,
-                            color: Color::WHITE,
-                            ..default()
-                        },
-                    ),
-                    style: Style {
-                        justify_self: JustifySelf::Center,
-                        margin: UiRect {
-                            top: Val::Px(90.0 + (i as f32 * 20.0)),
-                            ..default()
-                        },
-                        ..default()
-                    },
-                    ..default()
-                },
-                StateScoped(PlayingState::GameOver),
-                StateScoped(Screen::Leaderboard),
-            ));
+        for mut list in &mut list_query {
+            list.set_rows(score_rows(&res.records, &own_scores, &status));
         }
     }
 }
@@ -1,11 +1,14 @@
 //! The game's main screen states and transitions between them.
 
 mod credits;
+mod hud;
 pub mod leaderboard;
 mod loading;
 mod playing;
 mod splash;
+mod stats;
 mod title;
+mod versus;
 
 use bevy::prelude::*;
 
@@ -18,8 +21,10 @@ pub(super) fn plugin(app: &mut App) {
         loading::plugin,
         title::plugin,
         credits::plugin,
+        hud::plugin,
         playing::plugin,
         leaderboard::plugin,
+        versus::plugin,
     ));
 }
 
@@ -6,22 +6,37 @@ use bevy::{
     },
     prelude::*,
 };
-use bevy_http_client::{
-    prelude::{HttpTypedRequestTrait, TypedRequest, TypedResponse},
-    HttpClient,
-};
-use serde::{Deserialize, Serialize};
 
-use super::{title::TitleAction, PlayingState, Screen};
-use crate::game::{
-    assets::{HandleMap, ImageKey, SoundtrackKey},
-    audio::{sfx::StopAllLoopingSfx, soundtrack::PlaySoundtrack},
-    spawn::level::{Scoresource, SpawnLevel},
+use super::{
+    leaderboard::{score_rows, status_text, LeaderboardAction},
+    stats::spawn_stats_panel,
+    title::TitleAction,
+    PlayingState, Screen,
+};
+use crate::{
+    game::{
+        assets::{HandleMap, ImageKey},
+        audio::sfx::StopAllLoopingSfx,
+        difficulty::RunRules,
+        spawn::{
+            bench::is_versus,
+            level::{Scoresource, SpawnLevel},
+        },
+        stats::RunStats,
+    },
+    leaderboard::{
+        FetchScores, LeaderboardStatus, NameError, NameRules, OwnScores, RequestState, ScoreFilter,
+        ScoresFetched,
+    },
+    ui::prelude::*,
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Playing), enter_playing);
-    app.add_systems(OnEnter(PlayingState::GameOver), game_over);
+    app.add_systems(
+        OnEnter(PlayingState::GameOver),
+        game_over.run_if(not(is_versus)),
+    );
     app.add_systems(OnExit(Screen::Playing), exit_playing);
     app.add_systems(OnExit(PlayingState::GameOver), exit_gameover);
 
@@ -37,17 +52,14 @@ pub(super) fn plugin(app: &mut App) {
 
     app.insert_state(PlayingState::Playing);
 
-    app.register_request_type::<Vec<LeaderboardRecord>>();
-    app.register_request_type::<LeaderboardBody>();
     app.add_systems(
         Update,
-        handle_response.run_if(in_state(PlayingState::GameOver)),
+        (handle_response, update_submit_button).run_if(in_state(PlayingState::GameOver)),
     );
 }
 
 fn enter_playing(mut commands: Commands, mut next_state: ResMut<NextState<PlayingState>>) {
     commands.trigger(SpawnLevel);
-    commands.trigger(PlaySoundtrack::Key(SoundtrackKey::Gameplay));
     next_state.set(PlayingState::Playing);
 }
 
@@ -56,24 +68,21 @@ fn exit_playing(mut commands: Commands, mut next_state: ResMut<NextState<Playing
     next_state.set(PlayingState::Disabled);
 }
 
-fn exit_gameover(mut commands: Commands, mut next_state: ResMut<NextState<PlayingState>>) {
+fn exit_gameover(mut next_state: ResMut<NextState<PlayingState>>) {
     next_state.set(PlayingState::Disabled);
 }
 
-fn return_to_title_screen(mut next_screen: ResMut<NextState<Screen>>, mut commands: Commands) {
-    commands.trigger(PlaySoundtrack::Key(SoundtrackKey::Menu));
+fn return_to_title_screen(mut next_screen: ResMut<NextState<Screen>>) {
     next_screen.set(Screen::Title);
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct LeaderboardBody {
-    name: String,
-    score: f32,
-}
-
 #[derive(Component)]
 struct NameInput;
 
+/// Explains why the name can't be submitted.
+#[derive(Component)]
+struct NameHint;
+
 #[derive(Resource)]
 pub struct NameResource(pub Option<String>);
 
@@ -85,12 +94,17 @@ fn game_over(
     scoresource: Res<Scoresource>,
     images: Res<HandleMap<ImageKey>>,
     name: Res<NameResource>,
-    mut ev_request: EventWriter<TypedRequest<Vec<LeaderboardRecord>>>,
+    stats: Res<RunStats>,
+    rules: Res<RunRules>,
+    mut status: ResMut<LeaderboardStatus>,
 ) {
-    get_scores(ev_request);
+    status.new_run();
+    commands.trigger(FetchScores(ScoreFilter::for_run(
+        rules.mode,
+        rules.difficulty,
+    )));
 
     commands.trigger(StopAllLoopingSfx);
-    commands.trigger(PlaySoundtrack::Key(SoundtrackKey::Credits));
     commands.spawn((
         SpriteBundle {
             transform: Transform::from_translation(Vec3::new(0.0, 0.0, 500.0)),
@@ -110,7 +124,7 @@ fn game_over(
             text: Text::from_section(
                 "Game Over",
                 TextStyle {
-                    font_size: 100.0,
+                    font_size: ui_palette::TITLE_FONT_SIZE,
                     color: Color::WHITE,
                     ..default()
                 },
@@ -121,6 +135,7 @@ fn game_over(
             },
             ..default()
         },
+        ScaledText(ui_palette::TITLE_FONT_SIZE),
         StateScoped(PlayingState::GameOver),
         StateScoped(Screen::Playing),
     ));
@@ -130,7 +145,7 @@ fn game_over(
             text: Text::from_section(
                 "Tickery Tockery ran out of oil!",
                 TextStyle {
-                    font_size: 30.0,
+                    font_size: ui_palette::BODY_FONT_SIZE,
                     color: Color::WHITE,
                     ..default()
                 },
@@ -145,6 +160,7 @@ fn game_over(
             },
             ..default()
         },
+        ScaledText(ui_palette::BODY_FONT_SIZE),
         StateScoped(PlayingState::GameOver),
         StateScoped(Screen::Playing),
     ));
@@ -155,7 +171,7 @@ fn game_over(
             text: Text::from_section(
                 score_string,
                 TextStyle {
-                    font_size: 60.0,
+                    font_size: ui_palette::SCORE_FONT_SIZE,
                     color: Color::WHITE,
                     ..default()
                 },
@@ -170,41 +186,70 @@ fn game_over(
             },
             ..default()
         },
+        ScaledText(ui_palette::SCORE_FONT_SIZE),
         StateScoped(PlayingState::GameOver),
         StateScoped(Screen::Playing),
     ));
 
-    let t = if name.0.is_none() {
-        "Type your name".to_string()
+    if rules.ranked() {
+        spawn_name_entry(&mut commands, &name, &images);
     } else {
-        name.0.clone().unwrap()
-    };
-    commands.spawn((
-        TextBundle {
-            text: Text::from_section(
-                t,
-                TextStyle {
-                    font_size: 30.0,
-                    color: Color::WHITE,
-                    ..default()
-                },
-            ),
-            style: Style {
-                justify_self: JustifySelf::Center,
-                margin: UiRect {
-                    top: Val::Px(450.0),
-                    left: Val::Px(-250.0),
+        commands.spawn((
+            TextBundle {
+                text: Text::from_section(
+                    format!("{} runs aren't ranked", rules.difficulty.label()),
+                    TextStyle {
+                        font_size: ui_palette::BODY_FONT_SIZE,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                style: Style {
+                    justify_self: JustifySelf::Center,
+                    margin: UiRect {
+                        top: Val::Px(450.0),
+                        left: Val::Px(-250.0),
+                        ..default()
+                    },
                     ..default()
                 },
                 ..default()
             },
-            ..default()
-        },
+            ScaledText(ui_palette::BODY_FONT_SIZE),
+            StateScoped(PlayingState::GameOver),
+            StateScoped(Screen::Playing),
+        ));
+    }
+
+    commands.spawn((
+        status_text(600.0),
         StateScoped(PlayingState::GameOver),
         StateScoped(Screen::Playing),
-        NameInput,
     ));
 
+    commands
+        .button("Retry")
+        .insert(Style {
+            width: Val::Px(213.0),
+            height: Val::Px(63.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            align_self: AlignSelf::Center,
+            justify_self: JustifySelf::Center,
+            margin: UiRect {
+                top: Val::Px(350.0),
+                left: Val::Px(250.0),
+                ..default()
+            },
+            ..default()
+        })
+        .insert((
+            LeaderboardAction::Retry,
+            Visibility::Hidden,
+            StateScoped(PlayingState::GameOver),
+            StateScoped(Screen::Playing),
+        ));
+
     commands.spawn((
         ButtonBundle {
             style: Style {
@@ -215,22 +260,21 @@ fn game_over(
                 align_self: AlignSelf::Center,
                 justify_self: JustifySelf::Center,
                 margin: UiRect {
-                    top: Val::Px(200.0),
-                    left: Val::Px(250.0),
+                    top: Val::Px(500.0),
+                    left: Val::Px(-250.0),
                     ..default()
                 },
                 ..default()
             },
             image: UiImage {
-                texture: images[&ImageKey::SubmitButton].clone_weak(),
+                texture: images[&ImageKey::StartButton].clone_weak(),
                 ..default()
             },
             ..default()
         },
-        TitleAction::SubmitScore,
+        TitleAction::Menu,
         StateScoped(PlayingState::GameOver),
         StateScoped(Screen::Playing),
-        SubmitScoreButton,
     ));
 
     commands.spawn((
@@ -244,178 +288,248 @@ fn game_over(
                 justify_self: JustifySelf::Center,
                 margin: UiRect {
                     top: Val::Px(500.0),
-                    left: Val::Px(-250.0),
+                    right: Val::Px(-250.0),
                     ..default()
                 },
                 ..default()
             },
             image: UiImage {
-                texture: images[&ImageKey::StartButton].clone_weak(),
+                texture: images[&ImageKey::CreditsButton].clone_weak(),
                 ..default()
             },
             ..default()
         },
-        TitleAction::Menu,
+        TitleAction::Credits,
         StateScoped(PlayingState::GameOver),
         StateScoped(Screen::Playing),
     ));
 
     commands.spawn((
-        ButtonBundle {
+        TextBundle {
+            text: Text::from_section(
+                "Leaderboard",
+                TextStyle {
+                    font_size: ui_palette::BODY_FONT_SIZE,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ),
             style: Style {
-                width: Val::Px(213.0),
-                height: Val::Px(63.0),
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::Center,
-                align_self: AlignSelf::Center,
                 justify_self: JustifySelf::Center,
                 margin: UiRect {
-                    top: Val::Px(500.0),
-                    right: Val::Px(-250.0),
+                    top: Val::Px(150.0),
                     ..default()
                 },
                 ..default()
             },
-            image: UiImage {
-                texture: images[&ImageKey::CreditsButton].clone_weak(),
+            ..default()
+        },
+        ScaledText(ui_palette::BODY_FONT_SIZE),
+        StateScoped(PlayingState::GameOver),
+        StateScoped(Screen::Playing),
+    ));
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    justify_self: JustifySelf::Center,
+                    margin: UiRect {
+                        top: Val::Px(185.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                ..default()
+            },
+            StateScoped(PlayingState::GameOver),
+            StateScoped(Screen::Playing),
+        ))
+        .with_children(|children| {
+            children.score_list(7);
+        });
+
+    spawn_stats_panel(&mut commands, &stats, 150.0).insert((
+        StateScoped(PlayingState::GameOver),
+        StateScoped(Screen::Playing),
+    ));
+}
+
+/// The name to submit the score under, and the button to submit it.
+fn spawn_name_entry(commands: &mut Commands, name: &NameResource, images: &HandleMap<ImageKey>) {
+    let t = if name.0.is_none() {
+        "Type your name".to_string()
+    } else {
+        name.0.clone().unwrap()
+    };
+    commands.spawn((
+        TextBundle {
+            text: Text::from_section(
+                t,
+                TextStyle {
+                    font_size: ui_palette::BODY_FONT_SIZE,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ),
+            style: Style {
+                justify_self: JustifySelf::Center,
+                margin: UiRect {
+                    top: Val::Px(450.0),
+                    left: Val::Px(-250.0),
+                    ..default()
+                },
                 ..default()
             },
             ..default()
         },
-        TitleAction::Credits,
+        ScaledText(ui_palette::BODY_FONT_SIZE),
         StateScoped(PlayingState::GameOver),
         StateScoped(Screen::Playing),
+        NameInput,
     ));
 
     commands.spawn((
         TextBundle {
             text: Text::from_section(
-                "Leaderboard",
+                "",
                 TextStyle {
-                    font_size: 30.0,
-                    color: Color::WHITE,
+                    font_size: ui_palette::STATUS_FONT_SIZE,
+                    color: Color::linear_rgb(1.0, 0.4, 0.3),
                     ..default()
                 },
             ),
             style: Style {
                 justify_self: JustifySelf::Center,
                 margin: UiRect {
-                    top: Val::Px(150.0),
+                    top: Val::Px(420.0),
+                    left: Val::Px(-250.0),
                     ..default()
                 },
                 ..default()
             },
             ..default()
         },
+        ScaledText(ui_palette::STATUS_FONT_SIZE),
         StateScoped(PlayingState::GameOver),
         StateScoped(Screen::Playing),
+        NameHint,
     ));
-}
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct LeaderboardRecord {
-    pub id: String,
-    pub name: String,
-    pub score: f32,
+    commands.spawn((
+        ButtonBundle {
+            style: Style {
+                width: Val::Px(213.0),
+                height: Val::Px(63.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                align_self: AlignSelf::Center,
+                justify_self: JustifySelf::Center,
+                margin: UiRect {
+                    top: Val::Px(200.0),
+                    left: Val::Px(250.0),
+                    ..default()
+                },
+                ..default()
+            },
+            image: UiImage {
+                texture: images[&ImageKey::SubmitButton].clone_weak(),
+                ..default()
+            },
+            ..default()
+        },
+        TitleAction::SubmitScore,
+        StateScoped(PlayingState::GameOver),
+        StateScoped(Screen::Playing),
+        SubmitScoreButton,
+    ));
 }
 
-pub fn submit_score(
-    name: String,
-    score: f32,
-    mut ev_request: &mut EventWriter<TypedRequest<LeaderboardBody>>,
+/// Only offer to submit a score that isn't already on its way,
+/// and only once the name is valid.
+fn update_submit_button(
+    mut commands: Commands,
+    status: Res<LeaderboardStatus>,
+    name: Res<NameResource>,
+    rules: Res<NameRules>,
+    mut button_query: Query<(Entity, &mut Visibility, Has<Disabled>), With<SubmitScoreButton>>,
 ) {
-    let body = LeaderboardBody { name, score };
-    ev_request.send(
-        HttpClient::new()
-            .post("https://sr5t5qmb4c.execute-api.us-east-1.amazonaws.com/prod/leaderboard")
-            .json(&body)
-            .with_type::<LeaderboardBody>(),
-    );
-}
-
-pub fn get_scores(mut ev_request: EventWriter<TypedRequest<Vec<LeaderboardRecord>>>) {
-    ev_request.send(
-        HttpClient::new()
-            .get("https://sr5t5qmb4c.execute-api.us-east-1.amazonaws.com/prod/leaderboard")
-            .with_type::<Vec<LeaderboardRecord>>(),
-    );
+    let valid = name
+        .0
+        .as_deref()
+        .is_some_and(|name| rules.validate(name).is_ok());
+    for (entity, mut visibility, disabled) in &mut button_query {
+        visibility.set_if_neq(match status.submit {
+            RequestState::Idle => Visibility::Inherited,
+            _ => Visibility::Hidden,
+        });
+        if valid && disabled {
+            commands.entity(entity).remove::<Disabled>();
+        } else if !valid && !disabled {
+            commands.entity(entity).insert(Disabled);
+        }
+    }
 }
 
 fn handle_response(
-    mut commands: Commands,
-    mut ev_response: EventReader<TypedResponse<Vec<LeaderboardRecord>>>,
+    mut ev_response: EventReader<ScoresFetched>,
+    status: Res<LeaderboardStatus>,
+    own_scores: Res<OwnScores>,
+    mut list_query: Query<&mut ScoreList>,
 ) {
     for res in ev_response.read() {
-        for (i, score) in res.iter().take(10).enumerate() {
-            let text = format!("{}. {} - {:.2}", i + 1, score.name, score.score);
-            commands.spawn((
-                TextBundle {
-                    text: Text::from_section(
-                        text,
-                        TextStyle {
-                            font_size: 20.0,
-                            color: Color::WHITE,
-                            ..default()
-                        },
-                    ),
-                    style: Style {
-                        justify_self: JustifySelf::Center,
-                        margin: UiRect {
-                            top: Val::Px(190.0 + (i as f32 * 20.0)),
-                            ..default()
-                        },
-                        ..default()
-                    },
-                    ..default()
-                },
-                StateScoped(PlayingState::GameOver),
-                StateScoped(Screen::Playing),
-            ));
+        for mut list in &mut list_query {
+            list.set_rows(score_rows(&res.records, &own_scores, &status));
+            if status.own_id().is_some() {
+                list.scroll_to_own();
+            }
         }
     }
 }
 
 fn name_input(
     mut evr_kbd: EventReader<KeyboardInput>,
-    mut query: Query<(&NameInput, &mut Text)>,
+    mut input_query: Query<&mut Text, With<NameInput>>,
+    mut hint_query: Query<&mut Text, (With<NameHint>, Without<NameInput>)>,
     mut name: ResMut<NameResource>,
+    rules: Res<NameRules>,
 ) {
     for ev in evr_kbd.read() {
         if ev.state == ButtonState::Released {
             continue;
         }
 
-        if name.0.is_none() {
-            name.0 = Some("".to_string());
-        }
-
-        match &ev.logical_key {
+        let mut n = name.0.take().unwrap_or_default();
+        let typed = match &ev.logical_key {
             keyboard::Key::Backspace => {
-                let mut n = name.0.clone().unwrap();
                 n.pop();
-                if n.is_empty() {
-                    name.0 = None;
-                } else {
-                    name.0 = Some(n);
-                }
+                ""
             }
-            keyboard::Key::Character(c) => {
-                if c.chars().any(|c| c.is_control()) {
-                    continue;
-                }
-                let mut n = name.0.clone().unwrap();
-                n.push_str(c);
-                name.0 = Some(n);
+            keyboard::Key::Space => " ",
+            keyboard::Key::Character(c) => c.as_str(),
+            _ => "",
+        };
+        // Don't even take what couldn't be submitted.
+        for c in typed.chars().filter(|&c| NameRules::allows_char(c)) {
+            if n.chars().count() < rules.max_chars {
+                n.push(c);
             }
-            _ => {}
         }
+        name.0 = (!n.is_empty()).then_some(n);
     }
 
-    for (_, mut text) in query.iter_mut() {
-        if name.0.is_none() {
-            text.sections[0].value = "Type your name".to_string();
-        } else {
-            text.sections[0].value = name.0.clone().unwrap();
-        }
+    for mut text in &mut input_query {
+        text.sections[0].value = match &name.0 {
+            Some(name) => name.clone(),
+            None => "Type your name".to_string(),
+        };
+    }
+
+    let hint = match rules.validate(name.0.as_deref().unwrap_or_default()) {
+        Ok(_) | Err(NameError::Empty) => String::new(),
+        Err(e) => e.to_string(),
+    };
+    for mut text in &mut hint_query {
+        text.sections[0].value.clone_from(&hint);
     }
 }
@@ -0,0 +1,109 @@
+//! The panel showing how a run went, shown when it ends.
+
+use bevy::{ecs::system::EntityCommands, prelude::*};
+
+use crate::{
+    game::stats::RunStats,
+    tween::{Tween, POP_IN_SCALE},
+    ui::prelude::*,
+};
+
+const PANEL_WIDTH: f32 = 330.0;
+
+/// Label and value of every line on the panel.
+pub(super) fn stat_lines(stats: &RunStats) -> Vec<(String, String)> {
+    let mut lines = vec![
+        (
+            "Time survived".to_string(),
+            format_duration(stats.duration_secs),
+        ),
+        ("Most clocks".to_string(), stats.peak_clocks.to_string()),
+    ];
+    for (i, clock) in stats.clocks.iter().enumerate() {
+        lines.push((
+            format!("Clock {} in sync", i + 1),
+            format!("{:.0}%", clock.synced_fraction() * 100.0),
+        ));
+    }
+    lines.extend([
+        ("Winding".to_string(), format!("{:.1} s", stats.wind_secs)),
+        ("Oil drunk".to_string(), format!("{:.0}", stats.oil_drunk)),
+        ("Clocks run down".to_string(), stats.run_downs.to_string()),
+        (
+            "Best sync streak".to_string(),
+            format!("{:.1} s", stats.best_sync_streak_secs),
+        ),
+    ]);
+    lines
+}
+
+/// `m:ss`, e.g. `1:05`.
+pub(super) fn format_duration(secs: f32) -> String {
+    let secs = secs.max(0.0) as u32;
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+pub(super) fn spawn_stats_panel<'a>(
+    commands: &'a mut Commands,
+    stats: &RunStats,
+    top: f32,
+) -> EntityCommands<'a> {
+    let mut panel = commands.spawn((
+        Name::new("Stats Panel"),
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(30.0),
+                top: Val::Px(top),
+                width: Val::Px(PANEL_WIDTH),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                padding: UiRect::all(Val::Px(12.0)),
+                ..default()
+            },
+            background_color: ui_palette::NODE_BACKGROUND.with_alpha(0.4).into(),
+            transform: Transform::from_scale(Vec3::splat(POP_IN_SCALE)),
+            ..default()
+        },
+        Tween::pop_in(),
+    ));
+    panel.with_children(|children| {
+        children.spawn((
+            TextBundle::from_section(
+                "This run",
+                TextStyle {
+                    font_size: ui_palette::PANEL_HEADER_FONT_SIZE,
+                    color: ui_palette::HEADER_TEXT,
+                    ..default()
+                },
+            ),
+            ScaledText(ui_palette::PANEL_HEADER_FONT_SIZE),
+        ));
+        for (label, value) in stat_lines(stats) {
+            children
+                .spawn(NodeBundle {
+                    style: Style {
+                        justify_content: JustifyContent::SpaceBetween,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|children| {
+                    for text in [label, value] {
+                        children.spawn((
+                            TextBundle::from_section(
+                                text,
+                                TextStyle {
+                                    font_size: ui_palette::ROW_FONT_SIZE,
+                                    color: ui_palette::BUTTON_TEXT,
+                                    ..default()
+                                },
+                            ),
+                            ScaledText(ui_palette::ROW_FONT_SIZE),
+                        ));
+                    }
+                });
+        }
+    });
+    panel
+}
@@ -1,16 +1,19 @@
 //! The title screen that appears when the game starts.
 
 use bevy::{prelude::*, window::PrimaryWindow};
-use bevy_http_client::prelude::TypedRequest;
 
-use super::{
-    playing::{submit_score, LeaderboardBody, NameResource, SubmitScoreButton},
-    PlayingState, Screen,
-};
+use super::{playing::NameResource, PlayingState, Screen};
 use crate::{
     game::{
+        accessibility::{AccessibilityOption, AccessibilitySettings},
         assets::{FontKey, HandleMap, ImageKey},
-        spawn::level::Scoresource,
+        difficulty::{DifficultyChoice, RunRules},
+        effects::{EffectKind, EffectsSettings},
+        run::RunRecorder,
+        spawn::level::{RunDuration, Scoresource},
+    },
+    leaderboard::{
+        run_key, Difficulty, GameMode, LeaderboardBody, NameRules, RunInfo, SubmitScore,
     },
     ui::prelude::*,
 };
@@ -19,7 +22,16 @@ pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Title), enter_title);
 
     app.register_type::<TitleAction>();
-    app.add_systems(Update, handle_title_action.run_if(in_state(Screen::Title)));
+    app.add_systems(
+        Update,
+        (
+            handle_title_action,
+            show_difficulty,
+            show_effects,
+            show_accessibility,
+        )
+            .run_if(in_state(Screen::Title)),
+    );
     app.add_systems(
         Update,
         handle_title_action.run_if(in_state(PlayingState::GameOver)),
@@ -37,6 +49,13 @@ pub enum TitleAction {
     Menu,
     SubmitScore,
     Leaderboard,
+    Difficulty(Difficulty),
+    Mode(GameMode),
+    /// Turn an effect on or off.
+    Effect(EffectKind),
+    Accessibility(AccessibilityOption),
+    /// Go to the next text size.
+    TextScale,
 }
 
 #[derive(Component)]
@@ -45,6 +64,10 @@ struct TitleHand;
 #[derive(Component)]
 struct Gear;
 
+/// Describes the picked difficulty and mode.
+#[derive(Component)]
+struct DifficultyNote;
+
 fn enter_title(
     mut commands: Commands,
     images: Res<HandleMap<ImageKey>>,
@@ -177,6 +200,71 @@ fn enter_title(
         StateScoped(Screen::Title),
     ));
 
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    bottom: Val::Px(30.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(8.0),
+                    ..default()
+                },
+                ..default()
+            },
+            StateScoped(Screen::Title),
+        ))
+        .with_children(|children| {
+            children
+                .spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Val::Px(10.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|children| {
+                    for mode in GameMode::ALL {
+                        children
+                            .small_button(mode.label())
+                            .insert(TitleAction::Mode(mode));
+                    }
+                    for difficulty in Difficulty::ALL {
+                        children
+                            .small_button(difficulty.label())
+                            .insert(TitleAction::Difficulty(difficulty));
+                    }
+                });
+            children.label("").insert(DifficultyNote);
+            children
+                .spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Val::Px(10.0),
+                        row_gap: Val::Px(8.0),
+                        flex_wrap: FlexWrap::Wrap,
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|children| {
+                    for kind in EffectKind::ALL {
+                        children
+                            .small_button(kind.label())
+                            .insert(TitleAction::Effect(kind));
+                    }
+                    for option in AccessibilityOption::ALL {
+                        children
+                            .small_button(option.label())
+                            .insert(TitleAction::Accessibility(option));
+                    }
+                    // Labelled by `show_accessibility`.
+                    children.small_button("").insert(TitleAction::TextScale);
+                });
+        });
+
     commands.spawn((
         SpriteBundle {
             texture: images[&ImageKey::TitleBackground].clone_weak(),
@@ -234,7 +322,7 @@ fn handle_title_action(
     mut commands: Commands,
     time: Res<Time>,
     mut next_screen: ResMut<NextState<Screen>>,
-    mut button_query: InteractionQuery<&TitleAction>,
+    mut button_query: InteractionQuery<(&TitleAction, Has<Disabled>)>,
     mut hand_query: Query<&mut Transform, With<TitleHand>>,
     #[cfg(not(target_family = "wasm"))] mut app_exit: EventWriter<AppExit>,
     q_window: Query<&Window, With<PrimaryWindow>>,
@@ -242,11 +330,20 @@ fn handle_title_action(
     mut gears: Query<&mut Transform, (With<Gear>, Without<TitleHand>)>,
     name: Res<NameResource>,
     scoresource: Res<Scoresource>,
-    submit_score_button: Query<Entity, With<SubmitScoreButton>>,
-    mut ev_request: EventWriter<TypedRequest<LeaderboardBody>>,
+    run_duration: Res<RunDuration>,
+    recorder: Res<RunRecorder>,
+    rules: Res<NameRules>,
+    run_rules: Res<RunRules>,
+    (mut difficulty_choice, mut effects, mut accessibility): (
+        ResMut<DifficultyChoice>,
+        ResMut<EffectsSettings>,
+        ResMut<AccessibilitySettings>,
+    ),
 ) {
-    for mut gear in gears.iter_mut() {
-        gear.rotate_z(0.1 * time.delta_seconds());
+    if !accessibility.reduced_motion {
+        for mut gear in gears.iter_mut() {
+            gear.rotate_z(0.1 * time.delta_seconds());
+        }
     }
 
     let hand = hand_query.get_single_mut();
@@ -273,8 +370,8 @@ fn handle_title_action(
         }
     }
 
-    for (interaction, action) in &mut button_query {
-        if matches!(interaction, Interaction::Pressed) {
+    for (interaction, (action, disabled)) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) && !disabled {
             match action {
                 TitleAction::Play => {
                     next_screen.set(Screen::Playing);
@@ -287,19 +384,137 @@ fn handle_title_action(
                     app_exit.send(AppExit::Success);
                 }
                 TitleAction::SubmitScore => {
-                    let button = submit_score_button.get_single();
-                    if button.is_ok() {
-                        let e = commands.get_entity(button.unwrap());
-                        if let Some(e) = e {
-                            e.despawn_recursive();
-                        }
-                    }
-                    submit_score(name.0.clone().unwrap(), scoresource.0, &mut ev_request);
+                    let Some(Ok(name)) = name.0.as_deref().map(|name| rules.validate(name)) else {
+                        continue;
+                    };
+                    let body = LeaderboardBody {
+                        name,
+                        score: scoresource.0,
+                        run: RunInfo::finished(
+                            run_rules.mode,
+                            run_rules.difficulty,
+                            run_duration.0,
+                        ),
+                        summary: Some(recorder.summary(&run_rules)),
+                    };
+                    commands.trigger(SubmitScore(body.sign(run_key())));
                 }
                 TitleAction::Leaderboard => {
                     next_screen.set(Screen::Leaderboard);
                 }
+                TitleAction::Difficulty(difficulty) => {
+                    if difficulty_choice.difficulty != *difficulty {
+                        difficulty_choice.select(*difficulty);
+                    }
+                }
+                TitleAction::Mode(mode) => {
+                    if difficulty_choice.mode != *mode {
+                        difficulty_choice.select_mode(*mode);
+                    }
+                }
+                TitleAction::Effect(kind) => effects.toggle(*kind),
+                TitleAction::Accessibility(option) => accessibility.toggle(*option),
+                TitleAction::TextScale => accessibility.next_text_scale(),
+            }
+        }
+    }
+}
+
+fn show_difficulty(
+    choice: Res<DifficultyChoice>,
+    mut button_query: Query<(&TitleAction, &mut InteractionPalette, &mut BackgroundColor)>,
+    mut note_query: Query<(&mut Text, Ref<DifficultyNote>)>,
+) {
+    for (action, mut palette, mut background) in &mut button_query {
+        let selected = match *action {
+            TitleAction::Difficulty(difficulty) => difficulty == choice.difficulty,
+            TitleAction::Mode(mode) => mode == choice.mode,
+            _ => continue,
+        };
+        let none = if selected {
+            ui_palette::BUTTON_SELECTED_BACKGROUND
+        } else {
+            ui_palette::NODE_BACKGROUND
+        };
+        if palette.none != none {
+            palette.none = none;
+            *background = none.into();
+        }
+    }
+
+    for (mut text, note) in &mut note_query {
+        if !choice.is_changed() && !note.is_added() {
+            continue;
+        }
+        let mut value = format!("Scores x{:.2}", choice.settings().score_multiplier);
+        if choice.difficulty == Difficulty::Custom {
+            value.push_str(" - edit difficulty.json to change the rules");
+        }
+        if choice.mode.players() > 1 {
+            value.push_str(" - Tickery plays with the arrows and Enter, or a gamepad");
+        }
+        if !choice.mode.ranked() || !choice.difficulty.ranked() {
+            value.push_str(" - not ranked");
+        }
+        text.sections[0].value = value;
+    }
+}
+
+/// Effects that are on are shown selected.
+fn show_effects(
+    effects: Res<EffectsSettings>,
+    mut button_query: Query<(&TitleAction, &mut InteractionPalette, &mut BackgroundColor)>,
+) {
+    for (action, mut palette, mut background) in &mut button_query {
+        let TitleAction::Effect(kind) = *action else {
+            continue;
+        };
+        let none = if effects.enabled(kind) {
+            ui_palette::BUTTON_SELECTED_BACKGROUND
+        } else {
+            ui_palette::NODE_BACKGROUND
+        };
+        if palette.none != none {
+            palette.none = none;
+            *background = none.into();
+        }
+    }
+}
+
+/// Options that are on are shown selected, and the text size button shows the size.
+fn show_accessibility(
+    settings: Res<AccessibilitySettings>,
+    mut button_query: Query<(
+        &TitleAction,
+        &Children,
+        &mut InteractionPalette,
+        &mut BackgroundColor,
+    )>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (action, children, mut palette, mut background) in &mut button_query {
+        match *action {
+            TitleAction::Accessibility(option) => {
+                let none = if settings.enabled(option) {
+                    ui_palette::BUTTON_SELECTED_BACKGROUND
+                } else {
+                    ui_palette::NODE_BACKGROUND
+                };
+                if palette.none != none {
+                    palette.none = none;
+                    *background = none.into();
+                }
+            }
+            TitleAction::TextScale => {
+                let label = settings.text_scale_label();
+                let mut text = text_query.iter_many_mut(children);
+                while let Some(mut text) = text.fetch_next() {
+                    if text.sections[0].value != label {
+                        text.sections[0].value.clone_from(&label);
+                    }
+                }
             }
+            _ => {}
         }
     }
 }
@@ -0,0 +1,87 @@
+//! The results of a versus match, shown instead of the game over screen.
+//! Versus isn't ranked, so there is no score to submit.
+
+use bevy::prelude::*;
+
+use super::{title::TitleAction, PlayingState, Screen};
+use crate::{
+    game::{
+        audio::sfx::StopAllLoopingSfx,
+        spawn::{
+            bench::{is_versus, Bench, Score},
+            clock::ClockController,
+            player::{Player, PLAYER_NAMES},
+        },
+    },
+    ui::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(PlayingState::GameOver),
+        show_results.run_if(is_versus),
+    );
+}
+
+fn show_results(
+    mut commands: Commands,
+    bench_query: Query<(&Bench, &Score)>,
+    player_query: Query<(&Player, &ClockController)>,
+) {
+    commands.trigger(StopAllLoopingSfx);
+
+    let mut scores: Vec<_> = bench_query
+        .iter()
+        .map(|(bench, score)| (bench.0, score.points))
+        .collect();
+    scores.sort_by_key(|&(bench, _)| bench);
+    let best = scores
+        .iter()
+        .map(|&(_, points)| points)
+        .fold(f32::MIN, f32::max);
+    let winners: Vec<_> = scores
+        .iter()
+        .filter(|&&(_, points)| points == best)
+        .collect();
+    let title = match winners.as_slice() {
+        [&(bench, _)] => format!("{} wins!", PLAYER_NAMES[bench]),
+        _ => "It's a draw!".to_string(),
+    };
+    let dry: Vec<_> = player_query
+        .iter()
+        .filter(|(_, controller)| controller.oil_level <= 0.0)
+        .map(|(player, _)| PLAYER_NAMES[player.0])
+        .collect();
+
+    commands.spawn((
+        SpriteBundle {
+            transform: Transform::from_translation(Vec3::new(0.0, 0.0, 500.0)),
+            sprite: Sprite {
+                custom_size: Some(Vec2::new(1280.0, 720.0)),
+                color: Color::linear_rgba(0.0, 0.0, 0.0, 0.9),
+                ..default()
+            },
+            ..default()
+        },
+        StateScoped(PlayingState::GameOver),
+        StateScoped(Screen::Playing),
+    ));
+
+    commands
+        .ui_root()
+        .insert((
+            Name::new("Versus Results"),
+            StateScoped(PlayingState::GameOver),
+            StateScoped(Screen::Playing),
+        ))
+        .with_children(|children| {
+            children.header(title);
+            if !dry.is_empty() {
+                children.label(format!("{} ran out of oil!", dry.join(" and ")));
+            }
+            for &(bench, points) in &scores {
+                children.label(format!("{}: {points:.2}", PLAYER_NAMES[bench]));
+            }
+            children.button("Menu").insert(TitleAction::Menu);
+        });
+}
@@ -0,0 +1,161 @@
+//! Timed, eased animation of an entity's [`Transform`].
+//! Give an entity a [`Tween`] and call [`Tween::to`] to send it somewhere.
+//! Steps started while another is playing are queued behind it rather than dropped,
+//! and the queue plays faster the longer it gets, so quick inputs are never lost
+//! and the entity doesn't fall far behind.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::AppSet;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Update, advance_tweens.in_set(AppSet::Update));
+}
+
+/// The scale things start at before [`Tween::pop_in`].
+pub const POP_IN_SCALE: f32 = 0.6;
+const POP_IN_SECS: f32 = 0.3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+pub enum Ease {
+    Linear,
+    QuadIn,
+    QuadOut,
+    #[default]
+    QuadInOut,
+    /// Overshoots a little before settling.
+    BackOut,
+}
+
+impl Ease {
+    /// Map linear progress from 0 to 1 onto the curve.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => t,
+            Self::QuadIn => t * t,
+            Self::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Self::QuadInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+                }
+            }
+            Self::BackOut => {
+                const OVERSHOOT: f32 = 1.70158;
+                let t = t - 1.0;
+                1.0 + (OVERSHOOT + 1.0) * t.powi(3) + OVERSHOOT * t.powi(2)
+            }
+        }
+    }
+}
+
+/// Where a [`Tween`] takes a transform. Parts left as `None` aren't animated.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TweenTarget {
+    pub translation: Option<Vec3>,
+    pub rotation: Option<Quat>,
+    pub scale: Option<Vec3>,
+}
+
+impl TweenTarget {
+    pub fn translation(translation: Vec3) -> Self {
+        Self {
+            translation: Some(translation),
+            ..default()
+        }
+    }
+
+    pub fn scale(scale: Vec3) -> Self {
+        Self {
+            scale: Some(scale),
+            ..default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TweenStep {
+    target: TweenTarget,
+    secs: f32,
+    ease: Ease,
+}
+
+#[derive(Component, Debug, Default)]
+pub struct Tween {
+    /// The transform when the current step started.
+    from: Transform,
+    current: Option<TweenStep>,
+    elapsed: f32,
+    queue: VecDeque<TweenStep>,
+}
+
+impl Tween {
+    /// A tween that starts playing right away.
+    pub fn new(target: TweenTarget, secs: f32, ease: Ease) -> Self {
+        let mut tween = Self::default();
+        tween.to(target, secs, ease);
+        tween
+    }
+
+    /// Queue a step after the ones already playing.
+    pub fn to(&mut self, target: TweenTarget, secs: f32, ease: Ease) {
+        self.queue.push_back(TweenStep { target, secs, ease });
+    }
+
+    /// Grow a UI node or sprite into view, starting from a transform scaled by [`POP_IN_SCALE`].
+    pub fn pop_in() -> Self {
+        Self::new(TweenTarget::scale(Vec3::ONE), POP_IN_SECS, Ease::BackOut)
+    }
+
+    /// Where the last queued step goes, if any are left.
+    pub fn heading(&self) -> Option<TweenTarget> {
+        self.queue
+            .back()
+            .or(self.current.as_ref())
+            .map(|step| step.target)
+    }
+}
+
+fn advance_tweens(time: Res<Time>, mut tween_query: Query<(&mut Tween, &mut Transform)>) {
+    for (mut tween, mut transform) in &mut tween_query {
+        if tween.current.is_none() {
+            let Some(step) = tween.queue.pop_front() else {
+                continue;
+            };
+            tween.current = Some(step);
+            tween.from = *transform;
+            tween.elapsed = 0.0;
+        }
+        let Some(step) = tween.current else {
+            continue;
+        };
+
+        // Catch up when steps are piling up.
+        let speed = 1.0 + tween.queue.len() as f32;
+        tween.elapsed += time.delta_seconds() * speed;
+        let progress = if step.secs > 0.0 {
+            (tween.elapsed / step.secs).min(1.0)
+        } else {
+            1.0
+        };
+        let t = step.ease.apply(progress);
+
+        let from = tween.from;
+        if let Some(translation) = step.target.translation {
+            transform.translation = from.translation.lerp(translation, t);
+        }
+        if let Some(rotation) = step.target.rotation {
+            transform.rotation = from.rotation.slerp(rotation, t);
+        }
+        if let Some(scale) = step.target.scale {
+            transform.scale = from.scale.lerp(scale, t);
+        }
+        if progress >= 1.0 {
+            tween.current = None;
+        }
+    }
+}
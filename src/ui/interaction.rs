@@ -1,10 +1,18 @@
 use bevy::prelude::*;
 
-use crate::game::{assets::SfxKey, audio::sfx::PlaySfx};
+use crate::game::audio::{bank::SfxCue, sfx::PlaySfx};
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<InteractionPalette>();
-    app.add_systems(Update, (apply_interaction_palette, trigger_interaction_sfx));
+    app.register_type::<Disabled>();
+    app.add_systems(
+        Update,
+        (
+            apply_interaction_palette,
+            apply_disabled,
+            trigger_interaction_sfx,
+        ),
+    );
 }
 
 pub type InteractionQuery<'w, 's, T> =
@@ -19,6 +27,29 @@ pub struct InteractionPalette {
     pub pressed: Color,
 }
 
+/// A button that can't be pressed right now.
+/// Action handlers are expected to ignore its interactions.
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Disabled;
+
+const DISABLED_TINT: Color = Color::srgba(0.5, 0.5, 0.5, 0.6);
+
+fn apply_disabled(
+    mut disabled: Query<&mut UiImage, (With<Disabled>, Added<Disabled>)>,
+    mut removed: RemovedComponents<Disabled>,
+    mut images: Query<&mut UiImage, Without<Disabled>>,
+) {
+    for mut image in &mut disabled {
+        image.color = DISABLED_TINT;
+    }
+    for entity in removed.read() {
+        if let Ok(mut image) = images.get_mut(entity) {
+            image.color = Color::WHITE;
+        }
+    }
+}
+
 fn apply_interaction_palette(
     mut palette_query: InteractionQuery<(&InteractionPalette, &mut BackgroundColor)>,
 ) {
@@ -33,15 +64,10 @@ fn apply_interaction_palette(
 }
 
 fn trigger_interaction_sfx(
-    mut interactions: Query<&Interaction, Changed<Interaction>>,
+    mut interactions: Query<&Interaction, (Changed<Interaction>, With<Button>, Without<Disabled>)>,
     mut commands: Commands,
 ) {
     for _ in &mut interactions {
-        let r = if rand::random::<f32>() > 0.5 {
-            SfxKey::MenuTick
-        } else {
-            SfxKey::MenuTock
-        };
-        commands.trigger(PlaySfx::Key(r));
+        commands.trigger(PlaySfx::Cue(SfxCue::MenuClick));
     }
 }
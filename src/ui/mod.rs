@@ -5,12 +5,18 @@
 
 pub mod interaction;
 pub mod palette;
+pub mod progress_bar;
+pub mod score_list;
+pub mod text_scale;
 mod widgets;
 
 pub mod prelude {
     pub use super::{
-        interaction::{InteractionPalette, InteractionQuery},
+        interaction::{Disabled, InteractionPalette, InteractionQuery},
         palette as ui_palette,
+        progress_bar::ProgressBar,
+        score_list::{ScoreList, ScoreRow},
+        text_scale::{ScaledText, TextScale},
         widgets::{Containers as _, Widgets as _},
     };
 }
@@ -18,5 +24,10 @@ pub mod prelude {
 use bevy::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins(interaction::plugin);
+    app.add_plugins((
+        interaction::plugin,
+        progress_bar::plugin,
+        score_list::plugin,
+        text_scale::plugin,
+    ));
 }
@@ -2,9 +2,26 @@ use bevy::prelude::*;
 
 pub const BUTTON_HOVERED_BACKGROUND: Color = Color::srgb(0.186, 0.328, 0.573);
 pub const BUTTON_PRESSED_BACKGROUND: Color = Color::srgb(0.286, 0.478, 0.773);
+pub const BUTTON_SELECTED_BACKGROUND: Color = Color::srgb(0.773, 0.553, 0.186);
 
 pub const BUTTON_TEXT: Color = Color::srgb(0.925, 0.925, 0.925);
 pub const LABEL_TEXT: Color = Color::srgb(0.867, 0.827, 0.412);
 pub const HEADER_TEXT: Color = Color::srgb(0.867, 0.827, 0.412);
 
 pub const NODE_BACKGROUND: Color = Color::srgb(0.286, 0.478, 0.773);
+pub const PROGRESS_BACKGROUND: Color = Color::srgba(0.1, 0.1, 0.1, 0.6);
+pub const PROGRESS_FILL: Color = Color::srgb(0.867, 0.827, 0.412);
+
+/// Font sizes at 100% [`TextScale`](super::text_scale::TextScale).
+pub const BUTTON_FONT_SIZE: f32 = 40.0;
+pub const SMALL_BUTTON_FONT_SIZE: f32 = 22.0;
+pub const HEADER_FONT_SIZE: f32 = 40.0;
+pub const LABEL_FONT_SIZE: f32 = 24.0;
+pub const FOOTER_FONT_SIZE: f32 = 18.0;
+pub const HUD_FONT_SIZE: f32 = 22.0;
+pub const PANEL_HEADER_FONT_SIZE: f32 = 26.0;
+pub const ROW_FONT_SIZE: f32 = 20.0;
+pub const STATUS_FONT_SIZE: f32 = 20.0;
+pub const BODY_FONT_SIZE: f32 = 30.0;
+pub const SCORE_FONT_SIZE: f32 = 60.0;
+pub const TITLE_FONT_SIZE: f32 = 100.0;
@@ -0,0 +1,30 @@
+//! A horizontal bar filled up to a fraction. Spawn it with [`Widgets::progress_bar`]
+//! and set its [`ProgressBar`] to move the fill.
+//!
+//! [`Widgets::progress_bar`]: super::widgets::Widgets::progress_bar
+
+use bevy::{prelude::*, ui::Val::*};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Update, render_progress_bars);
+}
+
+/// How full the bar is, from 0 to 1.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq)]
+pub struct ProgressBar(pub f32);
+
+#[derive(Component)]
+pub(super) struct ProgressBarFill;
+
+fn render_progress_bars(
+    bar_query: Query<(&ProgressBar, &Children), Changed<ProgressBar>>,
+    mut fill_query: Query<&mut Style, With<ProgressBarFill>>,
+) {
+    for (bar, children) in &bar_query {
+        for &child in children {
+            if let Ok(mut style) = fill_query.get_mut(child) {
+                style.width = Percent(bar.0.clamp(0.0, 1.0) * 100.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,278 @@
+//! A list of scores with rank, name and score columns.
+//! Scrolls with the mouse wheel and has buttons to page through it
+//! and to jump to the player's best score. Spawn it with [`Widgets::score_list`].
+//!
+//! [`Widgets::score_list`]: super::widgets::Widgets::score_list
+
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+    ui::Val::*,
+};
+
+use super::{interaction::InteractionQuery, palette::*, text_scale::ScaledText};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            scroll_score_lists,
+            handle_score_list_action,
+            render_score_lists,
+        )
+            .chain(),
+    );
+}
+
+pub const SCORE_ROW_HEIGHT: f32 = 24.0;
+/// Widths of the rank, name and score columns.
+const COLUMN_WIDTHS: [f32; 3] = [70.0, 250.0, 120.0];
+pub const SCORE_LIST_WIDTH: f32 = COLUMN_WIDTHS[0] + COLUMN_WIDTHS[1] + COLUMN_WIDTHS[2];
+const OWN_ROW_TEXT: Color = Color::linear_rgb(1.0, 0.75, 0.1);
+const OWN_ROW_BACKGROUND: Color = Color::srgba(1.0, 0.75, 0.1, 0.15);
+/// Pixels of mouse wheel movement that scroll by one row.
+const PIXELS_PER_ROW: f32 = 30.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreRow {
+    pub rank: usize,
+    pub name: String,
+    pub score: f32,
+    /// One of the player's own scores.
+    pub own: bool,
+    /// The score the player just submitted.
+    pub latest: bool,
+}
+
+#[derive(Component, Debug, Default)]
+pub struct ScoreList {
+    rows: Vec<ScoreRow>,
+    /// Index of the topmost visible row.
+    first: usize,
+    page_size: usize,
+    /// Whether any rows were set yet.
+    loaded: bool,
+}
+
+impl ScoreList {
+    pub fn new(page_size: usize) -> Self {
+        Self {
+            page_size: page_size.max(1),
+            ..default()
+        }
+    }
+
+    pub fn set_rows(&mut self, rows: Vec<ScoreRow>) {
+        self.rows = rows;
+        self.loaded = true;
+        self.scroll_to(self.first);
+    }
+
+    pub fn scroll_by(&mut self, rows: isize) {
+        self.scroll_to(self.first.saturating_add_signed(rows));
+    }
+
+    fn scroll_to(&mut self, first: usize) {
+        self.first = first.min(self.rows.len().saturating_sub(self.page_size));
+    }
+
+    /// Center the view on the player's best score, if it's in the list.
+    pub fn scroll_to_own(&mut self) {
+        if let Some(index) = self.rows.iter().position(|row| row.own) {
+            self.scroll_to(index.saturating_sub(self.page_size / 2));
+        }
+    }
+
+    fn page_count(&self) -> usize {
+        self.rows.len().div_ceil(self.page_size).max(1)
+    }
+
+    /// The page the top row is on, or the last page once the bottom row is shown.
+    fn page(&self) -> usize {
+        if self.first + self.page_size >= self.rows.len() {
+            self.page_count() - 1
+        } else {
+            self.first / self.page_size
+        }
+    }
+
+    fn visible_rows(&self) -> &[ScoreRow] {
+        let end = (self.first + self.page_size).min(self.rows.len());
+        &self.rows[self.first..end]
+    }
+
+    fn placement(&self) -> Option<usize> {
+        self.rows.iter().find(|row| row.latest).map(|row| row.rank)
+    }
+}
+
+/// The node holding the visible rows of a [`ScoreList`].
+#[derive(Component, Debug)]
+pub(super) struct ScoreListRows(pub Entity);
+
+/// The text under a [`ScoreList`] showing the page and the player's placement.
+#[derive(Component, Debug)]
+pub(super) struct ScoreListFooter(pub Entity);
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ScoreListAction {
+    PreviousPage(Entity),
+    NextPage(Entity),
+    AroundMe(Entity),
+}
+
+fn scroll_score_lists(
+    mut ev_wheel: EventReader<MouseWheel>,
+    mut list_query: Query<(&mut ScoreList, &Interaction)>,
+    mut scrolled: Local<f32>,
+) {
+    for ev in ev_wheel.read() {
+        *scrolled -= match ev.unit {
+            MouseScrollUnit::Line => ev.y,
+            MouseScrollUnit::Pixel => ev.y / PIXELS_PER_ROW,
+        };
+    }
+    let rows = scrolled.trunc();
+    if rows == 0.0 {
+        return;
+    }
+    *scrolled -= rows;
+
+    for (mut list, interaction) in &mut list_query {
+        if *interaction != Interaction::None {
+            list.scroll_by(rows as isize);
+        }
+    }
+}
+
+fn handle_score_list_action(
+    mut button_query: InteractionQuery<&ScoreListAction>,
+    mut list_query: Query<&mut ScoreList>,
+) {
+    for (interaction, action) in &mut button_query {
+        if !matches!(interaction, Interaction::Pressed) {
+            continue;
+        }
+        match *action {
+            ScoreListAction::PreviousPage(list) => {
+                if let Ok(mut list) = list_query.get_mut(list) {
+                    let page_size = list.page_size as isize;
+                    list.scroll_by(-page_size);
+                }
+            }
+            ScoreListAction::NextPage(list) => {
+                if let Ok(mut list) = list_query.get_mut(list) {
+                    let page_size = list.page_size as isize;
+                    list.scroll_by(page_size);
+                }
+            }
+            ScoreListAction::AroundMe(list) => {
+                if let Ok(mut list) = list_query.get_mut(list) {
+                    list.scroll_to_own();
+                }
+            }
+        }
+    }
+}
+
+fn render_score_lists(
+    mut commands: Commands,
+    list_query: Query<&ScoreList, Changed<ScoreList>>,
+    rows_query: Query<(Entity, &ScoreListRows)>,
+    mut footer_query: Query<(&mut Text, &ScoreListFooter)>,
+) {
+    for (entity, rows) in &rows_query {
+        let Ok(list) = list_query.get(rows.0) else {
+            continue;
+        };
+        commands
+            .entity(entity)
+            .despawn_descendants()
+            .with_children(|children| {
+                if list.loaded && list.rows.is_empty() {
+                    spawn_row(children, ["", "No scores yet", ""], LABEL_TEXT, None);
+                }
+                for row in list.visible_rows() {
+                    let (color, background) = if row.own {
+                        (OWN_ROW_TEXT, Some(OWN_ROW_BACKGROUND))
+                    } else {
+                        (BUTTON_TEXT, None)
+                    };
+                    spawn_row(
+                        children,
+                        [
+                            &format!("#{}", row.rank),
+                            &row.name,
+                            &format!("{:.2}", row.score),
+                        ],
+                        color,
+                        background,
+                    );
+                }
+            });
+    }
+
+    for (mut text, footer) in &mut footer_query {
+        let Ok(list) = list_query.get(footer.0) else {
+            continue;
+        };
+        let mut value = format!("Page {}/{}", list.page() + 1, list.page_count());
+        if let Some(rank) = list.placement() {
+            value.push_str(&format!("  -  You placed #{rank}"));
+        }
+        text.sections[0].value = value;
+    }
+}
+
+/// Spawn one line of the list, also used for the column headers.
+pub(super) fn spawn_row(
+    children: &mut ChildBuilder,
+    columns: [&str; 3],
+    color: Color,
+    background: Option<Color>,
+) {
+    children
+        .spawn(NodeBundle {
+            style: Style {
+                width: Px(SCORE_LIST_WIDTH),
+                height: Px(SCORE_ROW_HEIGHT),
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: background.unwrap_or(Color::NONE).into(),
+            ..default()
+        })
+        .with_children(|children| {
+            for (i, (text, width)) in columns.into_iter().zip(COLUMN_WIDTHS).enumerate() {
+                children
+                    .spawn(NodeBundle {
+                        style: Style {
+                            width: Px(width),
+                            padding: UiRect::horizontal(Px(6.0)),
+                            // Scores line up on the right.
+                            justify_content: if i == 2 {
+                                JustifyContent::FlexEnd
+                            } else {
+                                JustifyContent::FlexStart
+                            },
+                            overflow: Overflow::clip(),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|children| {
+                        children.spawn((
+                            TextBundle::from_section(
+                                text,
+                                TextStyle {
+                                    font_size: ROW_FONT_SIZE,
+                                    color,
+                                    ..default()
+                                },
+                            ),
+                            ScaledText(ROW_FONT_SIZE),
+                        ));
+                    });
+            }
+        });
+}
@@ -0,0 +1,36 @@
+//! Bigger UI text for players who need it. UI text, from the
+//! [`Widgets`](super::widgets::Widgets) builders and the screens alike, is sized
+//! from the palette and marked with [`ScaledText`], which follows the [`TextScale`].
+//! The title logo and text drawn in the world keep their size.
+
+use bevy::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<TextScale>();
+    app.add_systems(Update, scale_text);
+}
+
+/// How much bigger than its palette size UI text is drawn.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct TextScale(pub f32);
+
+impl Default for TextScale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Text that follows the [`TextScale`], with its font size at 100%.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ScaledText(pub f32);
+
+fn scale_text(scale: Res<TextScale>, mut text_query: Query<(Ref<ScaledText>, &mut Text)>) {
+    for (scaled, mut text) in &mut text_query {
+        if !scale.is_changed() && !scaled.is_added() {
+            continue;
+        }
+        for section in &mut text.sections {
+            section.style.font_size = scaled.0 * scale.0;
+        }
+    }
+}
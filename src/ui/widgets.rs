@@ -2,27 +2,52 @@
 
 use bevy::{ecs::system::EntityCommands, prelude::*, ui::Val::*};
 
-use super::{interaction::InteractionPalette, palette::*};
+use super::{
+    interaction::InteractionPalette,
+    palette::*,
+    progress_bar::{ProgressBar, ProgressBarFill},
+    score_list::{
+        spawn_row, ScoreList, ScoreListAction, ScoreListFooter, ScoreListRows, SCORE_ROW_HEIGHT,
+    },
+    text_scale::ScaledText,
+};
 
 /// An extension trait for spawning UI widgets.
 pub trait Widgets {
     /// Spawn a simple button with text.
-    fn button(&mut self, text: impl Into<String>) -> EntityCommands;
+    fn button(&mut self, text: impl Into<String>) -> EntityCommands<'_>;
+
+    /// Spawn a button with text, small enough to put several in a row.
+    fn small_button(&mut self, text: impl Into<String>) -> EntityCommands<'_>;
 
     /// Spawn a simple header label. Bigger than [`Widgets::label`].
-    fn header(&mut self, text: impl Into<String>) -> EntityCommands;
+    fn header(&mut self, text: impl Into<String>) -> EntityCommands<'_>;
 
     /// Spawn a simple text label.
-    fn label(&mut self, text: impl Into<String>) -> EntityCommands;
+    fn label(&mut self, text: impl Into<String>) -> EntityCommands<'_>;
+
+    /// Spawn a [`ScoreList`] showing `page_size` rows at a time.
+    fn score_list(&mut self, page_size: usize) -> EntityCommands<'_>;
+
+    /// Spawn an empty [`ProgressBar`].
+    fn progress_bar(&mut self, width: f32) -> EntityCommands<'_>;
 }
 
 impl<T: Spawn> Widgets for T {
-    fn button(&mut self, text: impl Into<String>) -> EntityCommands {
+    fn button(&mut self, text: impl Into<String>) -> EntityCommands<'_> {
+        sized_button(self, text, Px(200.0), Px(65.0), BUTTON_FONT_SIZE)
+    }
+
+    fn small_button(&mut self, text: impl Into<String>) -> EntityCommands<'_> {
+        sized_button(self, text, Px(140.0), Px(36.0), SMALL_BUTTON_FONT_SIZE)
+    }
+
+    fn header(&mut self, text: impl Into<String>) -> EntityCommands<'_> {
         let mut entity = self.spawn((
-            Name::new("Button"),
-            ButtonBundle {
+            Name::new("Header"),
+            NodeBundle {
                 style: Style {
-                    width: Px(200.0),
+                    width: Px(600.0),
                     height: Px(65.0),
                     justify_content: JustifyContent::Center,
                     align_items: AlignItems::Center,
@@ -31,98 +56,211 @@ impl<T: Spawn> Widgets for T {
                 background_color: BackgroundColor(NODE_BACKGROUND),
                 ..default()
             },
-            InteractionPalette {
-                none: NODE_BACKGROUND,
-                hovered: BUTTON_HOVERED_BACKGROUND,
-                pressed: BUTTON_PRESSED_BACKGROUND,
-            },
         ));
         entity.with_children(|children| {
             children.spawn((
-                Name::new("Button Text"),
+                Name::new("Header Text"),
                 TextBundle::from_section(
                     text,
                     TextStyle {
-                        font_size: 40.0,
-                        color: BUTTON_TEXT,
+                        font_size: HEADER_FONT_SIZE,
+                        color: HEADER_TEXT,
                         ..default()
                     },
                 ),
+                ScaledText(HEADER_FONT_SIZE),
             ));
         });
         entity
     }
 
-    fn header(&mut self, text: impl Into<String>) -> EntityCommands {
+    fn label(&mut self, text: impl Into<String>) -> EntityCommands<'_> {
         let mut entity = self.spawn((
-            Name::new("Header"),
+            Name::new("Label"),
             NodeBundle {
                 style: Style {
                     width: Px(600.0),
-                    height: Px(65.0),
                     justify_content: JustifyContent::Center,
                     align_items: AlignItems::Center,
                     ..default()
                 },
-                background_color: BackgroundColor(NODE_BACKGROUND),
                 ..default()
             },
         ));
         entity.with_children(|children| {
             children.spawn((
-                Name::new("Header Text"),
+                Name::new("Label Text"),
                 TextBundle::from_section(
                     text,
                     TextStyle {
-                        font_size: 40.0,
-                        color: HEADER_TEXT,
+                        font_size: LABEL_FONT_SIZE,
+                        color: LABEL_TEXT,
                         ..default()
                     },
                 ),
+                ScaledText(LABEL_FONT_SIZE),
             ));
         });
         entity
     }
 
-    fn label(&mut self, text: impl Into<String>) -> EntityCommands {
+    fn score_list(&mut self, page_size: usize) -> EntityCommands<'_> {
         let mut entity = self.spawn((
-            Name::new("Label"),
+            Name::new("Score List"),
             NodeBundle {
                 style: Style {
-                    width: Px(600.0),
-                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
                     align_items: AlignItems::Center,
+                    row_gap: Px(4.0),
                     ..default()
                 },
                 ..default()
             },
+            ScoreList::new(page_size),
+            // Lets the list scroll while hovered.
+            Interaction::default(),
         ));
+        let list = entity.id();
         entity.with_children(|children| {
+            spawn_row(
+                children,
+                ["Rank", "Name", "Score"],
+                BUTTON_TEXT,
+                Some(NODE_BACKGROUND),
+            );
             children.spawn((
-                Name::new("Label Text"),
+                Name::new("Score List Rows"),
+                NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        height: Px(page_size as f32 * SCORE_ROW_HEIGHT),
+                        ..default()
+                    },
+                    ..default()
+                },
+                ScoreListRows(list),
+            ));
+            children.spawn((
+                Name::new("Score List Footer"),
                 TextBundle::from_section(
-                    text,
+                    "",
                     TextStyle {
-                        font_size: 24.0,
+                        font_size: FOOTER_FONT_SIZE,
                         color: LABEL_TEXT,
                         ..default()
                     },
                 ),
+                ScaledText(FOOTER_FONT_SIZE),
+                ScoreListFooter(list),
             ));
+            children
+                .spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Px(10.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|children| {
+                    for (text, action) in [
+                        ("Previous", ScoreListAction::PreviousPage(list)),
+                        ("Around me", ScoreListAction::AroundMe(list)),
+                        ("Next", ScoreListAction::NextPage(list)),
+                    ] {
+                        children.small_button(text).insert(action);
+                    }
+                });
         });
         entity
     }
+
+    fn progress_bar(&mut self, width: f32) -> EntityCommands<'_> {
+        let mut entity = self.spawn((
+            Name::new("Progress Bar"),
+            NodeBundle {
+                style: Style {
+                    width: Px(width),
+                    height: Px(12.0),
+                    ..default()
+                },
+                background_color: BackgroundColor(PROGRESS_BACKGROUND),
+                ..default()
+            },
+            ProgressBar::default(),
+        ));
+        entity.with_children(|children| {
+            children.spawn((
+                Name::new("Progress Bar Fill"),
+                NodeBundle {
+                    style: Style {
+                        width: Percent(0.0),
+                        height: Percent(100.0),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(PROGRESS_FILL),
+                    ..default()
+                },
+                ProgressBarFill,
+            ));
+        });
+        entity
+    }
+}
+
+fn sized_button<T: Spawn>(
+    spawner: &mut T,
+    text: impl Into<String>,
+    width: Val,
+    height: Val,
+    font_size: f32,
+) -> EntityCommands<'_> {
+    let mut entity = spawner.spawn((
+        Name::new("Button"),
+        ButtonBundle {
+            // Grows to fit scaled up text.
+            style: Style {
+                min_width: width,
+                min_height: height,
+                padding: UiRect::horizontal(Px(8.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: BackgroundColor(NODE_BACKGROUND),
+            ..default()
+        },
+        InteractionPalette {
+            none: NODE_BACKGROUND,
+            hovered: BUTTON_HOVERED_BACKGROUND,
+            pressed: BUTTON_PRESSED_BACKGROUND,
+        },
+    ));
+    entity.with_children(|children| {
+        children.spawn((
+            Name::new("Button Text"),
+            TextBundle::from_section(
+                text,
+                TextStyle {
+                    font_size,
+                    color: BUTTON_TEXT,
+                    ..default()
+                },
+            ),
+            ScaledText(font_size),
+        ));
+    });
+    entity
 }
 
 /// An extension trait for spawning UI containers.
 pub trait Containers {
     /// Spawns a root node that covers the full screen
     /// and centers its content horizontally and vertically.
-    fn ui_root(&mut self) -> EntityCommands;
+    fn ui_root(&mut self) -> EntityCommands<'_>;
 }
 
 impl Containers for Commands<'_, '_> {
-    fn ui_root(&mut self) -> EntityCommands {
+    fn ui_root(&mut self) -> EntityCommands<'_> {
         self.spawn((
             Name::new("UI Root"),
             NodeBundle {
@@ -147,17 +285,17 @@ impl Containers for Commands<'_, '_> {
 /// are able to spawn entities.
 /// Ideally, this trait should be [part of Bevy itself](https://github.com/bevyengine/bevy/issues/14231).
 trait Spawn {
-    fn spawn<B: Bundle>(&mut self, bundle: B) -> EntityCommands;
+    fn spawn<B: Bundle>(&mut self, bundle: B) -> EntityCommands<'_>;
 }
 
 impl Spawn for Commands<'_, '_> {
-    fn spawn<B: Bundle>(&mut self, bundle: B) -> EntityCommands {
+    fn spawn<B: Bundle>(&mut self, bundle: B) -> EntityCommands<'_> {
         self.spawn(bundle)
     }
 }
 
 impl Spawn for ChildBuilder<'_> {
-    fn spawn<B: Bundle>(&mut self, bundle: B) -> EntityCommands {
+    fn spawn<B: Bundle>(&mut self, bundle: B) -> EntityCommands<'_> {
         self.spawn(bundle)
     }
 }